@@ -1,12 +1,11 @@
 import test from 'ava'
 import 'dotenv/config'
 
-import { InfluxDbClient, test as aTest } from '../index'
+import { InfluxDbClient } from '../index'
 
 test('Test sql query from cloud serverless', async (t) => {
   const client = new InfluxDbClient(process.env.SERVER_URL || '', process.env.API_TOKEN || '')
 
-  aTest()
   // const result = client.query({
   //   database: 'test',
   //   query: 'SELECT * FROM "tag_keys"',
@@ -1,19 +1,31 @@
 import { Bench } from 'tinybench'
 
-import { plus100 } from '../index.js'
+import { InfluxDbClient, Serializer } from '../index.js'
 
-function add(a: number) {
-  return a + 100
+// Needs a server with data: SERVER_URL, API_TOKEN, BENCH_DATABASE and BENCH_QUERY,
+// e.g. a query returning a few hundred thousand rows with dictionary encoded tags.
+const url = process.env.SERVER_URL ?? 'http://localhost:8181'
+const token = process.env.API_TOKEN
+const database = process.env.BENCH_DATABASE ?? 'bench'
+const query = process.env.BENCH_QUERY ?? 'SELECT * FROM cpu LIMIT 200000'
+
+async function drain(serializer: Serializer) {
+  const client = new InfluxDbClient(url, token, serializer)
+  let rows = 0
+  for await (const _ of client.query({ database, query })) {
+    rows++
+  }
+  return rows
 }
 
-const b = new Bench()
+const b = new Bench({ iterations: 10 })
 
-b.add('Native a + 100', () => {
-  plus100(10)
+b.add('library rows', async () => {
+  await drain(Serializer.Library)
 })
 
-b.add('JavaScript a + 100', () => {
-  add(10)
+b.add('unsafe rows', async () => {
+  await drain(Serializer.Unsafe)
 })
 
 await b.run()
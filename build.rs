@@ -28,5 +28,7 @@ fn main() {
   //     )
   //     .unwrap();
 
-  napi_build::setup();
+  if std::env::var_os("CARGO_FEATURE_NAPI").is_some() {
+    napi_build::setup();
+  }
 }
@@ -0,0 +1,150 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Implements `InfluxPoint` for a struct with named members.
+///
+/// `#[influx(measurement = "...")]` on the struct sets the measurement, the struct name is used
+/// without it. Members are marked `#[influx(tag)]`, `#[influx(field)]`, `#[influx(timestamp)]`
+/// or `#[influx(skip)]`, unmarked members are fields. `#[influx(rename = "...")]` changes the
+/// written name. Other attributes, like doc comments or serde attributes, are left alone.
+///
+/// Generic members need the bound of their role, e.g. `T: FieldValue`.
+#[proc_macro_derive(InfluxPoint, attributes(influx))]
+pub fn derive_influx_point(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  expand(input)
+    .unwrap_or_else(Error::into_compile_error)
+    .into()
+}
+
+enum Kind {
+  Tag,
+  Field,
+  Timestamp,
+  Skip,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+  let krate = quote!(::influxdb3_napi);
+
+  let mut measurement = None;
+  for attr in input
+    .attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("influx"))
+  {
+    attr.parse_nested_meta(|meta| {
+      if meta.path.is_ident("measurement") {
+        measurement = Some(meta.value()?.parse::<LitStr>()?.value());
+        Ok(())
+      } else {
+        Err(meta.error("expected `measurement = \"...\"`"))
+      }
+    })?;
+  }
+  let measurement = measurement.unwrap_or_else(|| input.ident.unraw().to_string());
+
+  let Data::Struct(data) = &input.data else {
+    return Err(Error::new_spanned(
+      &input.ident,
+      "InfluxPoint can only be derived for structs",
+    ));
+  };
+  let Fields::Named(fields) = &data.fields else {
+    return Err(Error::new_spanned(
+      &input.ident,
+      "InfluxPoint needs a struct with named members",
+    ));
+  };
+
+  let mut statements = Vec::new();
+  let mut has_timestamp = false;
+
+  for field in &fields.named {
+    let ident = field.ident.as_ref().expect("named member");
+    let mut name = ident.unraw().to_string();
+    let mut kind = None;
+
+    for attr in field
+      .attrs
+      .iter()
+      .filter(|attr| attr.path().is_ident("influx"))
+    {
+      attr.parse_nested_meta(|meta| {
+        let member_kind = if meta.path.is_ident("tag") {
+          Kind::Tag
+        } else if meta.path.is_ident("field") {
+          Kind::Field
+        } else if meta.path.is_ident("timestamp") {
+          Kind::Timestamp
+        } else if meta.path.is_ident("skip") {
+          Kind::Skip
+        } else if meta.path.is_ident("rename") {
+          name = meta.value()?.parse::<LitStr>()?.value();
+          return Ok(());
+        } else {
+          return Err(
+            meta.error("expected `tag`, `field`, `timestamp`, `skip` or `rename = \"...\"`"),
+          );
+        };
+
+        match kind.replace(member_kind) {
+          Some(_) => {
+            Err(meta.error("a member is only one of `tag`, `field`, `timestamp` or `skip`"))
+          }
+          None => Ok(()),
+        }
+      })?;
+    }
+
+    let statement = match kind.unwrap_or(Kind::Field) {
+      Kind::Tag => quote! {
+        if let Some(value) = #krate::point::typed::TagValue::tag_value(&self.#ident) {
+          point.set_tag(::std::string::String::from(#name), value);
+        }
+      },
+      Kind::Field => quote! {
+        #krate::point::typed::FieldValue::set_field(
+          &self.#ident,
+          &mut point,
+          ::std::string::String::from(#name),
+        );
+      },
+      Kind::Timestamp if has_timestamp => {
+        return Err(Error::new_spanned(
+          ident,
+          "only one member can be the timestamp",
+        ));
+      }
+      Kind::Timestamp => {
+        has_timestamp = true;
+        quote! {
+          if let Some(time) = #krate::point::typed::TimestampValue::timestamp_value(&self.#ident, precision) {
+            point.set_timestamp(time);
+          }
+        }
+      }
+      Kind::Skip => continue,
+    };
+    statements.push(statement);
+  }
+
+  let ident = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  Ok(quote! {
+    impl #impl_generics #krate::point::typed::InfluxPoint for #ident #ty_generics #where_clause {
+      fn to_point_with_precision(
+        &self,
+        precision: #krate::client::options::TimeUnitV2,
+      ) -> #krate::point::Point {
+        let mut point = #krate::point::Point::new(::std::string::String::from(#measurement));
+        #(#statements)*
+        point
+      }
+    }
+  })
+}
@@ -0,0 +1,36 @@
+/* auto-generated by NAPI-RS */
+/* eslint-disable */
+/**
+ * Batch of the `columnar` serializer: a typed array, or an array of booleans or strings, per
+ * column in schema order and the Arrow null bitmap of the columns that contain nulls.
+ */
+export type ColumnarBatch = {
+  numRows: number
+  columns: Record<
+    string,
+    | Int8Array
+    | Int16Array
+    | Int32Array
+    | BigInt64Array
+    | Uint8Array
+    | Uint16Array
+    | Uint32Array
+    | BigUint64Array
+    | Float32Array
+    | Float64Array
+    | Array<boolean | null>
+    | Array<string | null>
+  >
+  validity: Record<string, Uint8Array>
+}
+
+/** Stream of a query, its items depend on the serializer of the client. */
+export type QueryStream =
+  | ReadableStream<ReturnDataType>
+  | ReadableStream<Record<string, any>>
+  | ReadableStream<Buffer>
+  | ReadableStream<ColumnarBatch>
+
+/** Rows of a whole query, a single buffer for the raw and text serializers. */
+export type QueryRows = Array<ReturnDataType> | Array<Record<string, any>> | Buffer | Array<ColumnarBatch>
+
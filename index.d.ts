@@ -1,8 +1,74 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+/**
+ * Batch of the `columnar` serializer: a typed array, or an array of booleans or strings, per
+ * column in schema order and the Arrow null bitmap of the columns that contain nulls.
+ */
+export type ColumnarBatch = {
+  numRows: number
+  columns: Record<
+    string,
+    | Int8Array
+    | Int16Array
+    | Int32Array
+    | BigInt64Array
+    | Uint8Array
+    | Uint16Array
+    | Uint32Array
+    | BigUint64Array
+    | Float32Array
+    | Float64Array
+    | Array<boolean | null>
+    | Array<string | null>
+  >
+  validity: Record<string, Uint8Array>
+}
+
+/** Stream of a query, its items depend on the serializer of the client. */
+export type QueryStream =
+  | ReadableStream<ReturnDataType>
+  | ReadableStream<Record<string, any>>
+  | ReadableStream<Buffer>
+  | ReadableStream<ColumnarBatch>
+
+/** Rows of a whole query, a single buffer for the raw and text serializers. */
+export type QueryRows = Array<ReturnDataType> | Array<Record<string, any>> | Buffer | Array<ColumnarBatch>
+
 export declare class InfluxDbClient {
   constructor(addr: string, token?: string | undefined | null, serializer?: Serializer | undefined | null, options?: FlightOptions | undefined | null)
-  query(queryPayload: QueryPayload): Promise<number>
+  query(queryPayload: QueryPayload): QueryStream
+  /**
+   * Runs the query with a serializer that a native addon registered under `serializer` with
+   * `register_serializer`.
+   */
+  queryWith(serializer: string, queryPayload: QueryPayload): ReadableStream<any>
+  /**
+   * Runs the query and collects every row into a single array, or into a single Arrow IPC
+   * buffer for the `raw` serializer. Fails once more than `maxRows` rows are returned.
+   */
+  queryAll(queryPayload: QueryPayload, options?: QueryAllOptions | undefined | null): Promise<QueryRows>
+  /**
+   * Runs the query and writes the result to `path` without handing rows to JS.
+   * Returns the number of rows written.
+   */
+  queryToFile(queryPayload: QueryPayload, path: string, format: ExportFormat, options?: ExportOptions | undefined | null): Promise<number>
+  /**
+   * Prepares `query` on `database` with Flight SQL, so it is planned once and can be executed
+   * repeatedly with different parameters.
+   */
+  prepare(database: string, query: string): PreparedStatement
+  /** Lists the databases of an InfluxDB 3 Core or Enterprise server, other servers fail. */
+  listDatabases(): Promise<Array<string>>
+  /** Lists the tables of `database`, without the system tables of the server. */
+  listTables(database: string): Promise<Array<string>>
+  /** Returns the columns of `table` with their role (tag, field or time) and Arrow type. */
+  describeTable(database: string, table: string): Promise<TableDescription>
+  /**
+   * # Safety
+   *
+   * This function should not be called before the horsemen are ready.
+   */
+  write(lines: Array<string>, database: string, writeOptions?: WriteOptions | undefined | null, org?: string | undefined | null): Promise<void>
 }
 export type InfluxDBClient = InfluxDbClient
 
@@ -17,23 +83,22 @@ export declare class Point {
   setTag(name: string, value: string): void
   removeTag(name: string): void
   get tagNames(): Array<string>
-  getFloatField(name: string): number | null
   setFloatField(name: string, value: number): void
-  getIntField(name: string): number | null
   setIntField(name: string, value: number): void
-  getUintegerField(name: string): number | null
-  setUintegerField(name: string, value: number): void
-  getStringField(name: string): string | null
   setStringField(name: string, value: string): void
-  getBooleanField(name: string): boolean | null
   setBooleanField(name: string, value: boolean): void
   getFieldType(name: string): PointFieldType | null
-  setFields(values: Record<string, any>): void
-  removeField(name: string): void
   getFieldNames(): Array<string>
   hasFields(): boolean
   toLineProtocol(timePrecision?: TimeUnitV2 | undefined | null, defaultTags?: Record<string, string> | undefined | null): string | null
-
+  getFloatField(name: string): number | null
+  getIntField(name: string): number | null
+  getUintegerField(name: string): number | null
+  setUintegerField(name: string, value: number): void
+  getStringField(name: string): string | null
+  getBooleanField(name: string): boolean | null
+  setFields(values: Record<string, boolean | number | number | number | string>): void
+  removeField(name: string): void
 }
 
 export declare class PointValues {
@@ -46,31 +111,162 @@ export declare class PointValues {
   setTag(tagName: string, tagValue: string): void
   removeTag(tagName: string): void
   get tagNames(): Array<string>
-  getFloatField(name: string): number | null
   setFloatField(name: string, value: number): void
-  getIntField(name: string): number | null
   setIntField(name: string, value: number): void
-  getUintegerField(name: string): number | null
-  setUintegerField(name: string, value: number): void
-  getStringField(name: string): string | null
   setStringField(name: string, value: string): void
-  getBooleanField(name: string): boolean | null
   setBooleanField(name: string, value: boolean): void
   getFieldType(name: string): PointFieldType | null
-  getField(name: string, expectedType?: PointFieldType | undefined | null): any | null
-  setField(name: string, value: any, fieldType?: PointFieldType | undefined | null): void
-  setFields(values: Record<string, any>): void
-  removeField(name: string): void
   getFieldNames(): Array<string>
   hasFields(): boolean
+  getFloatField(name: string): number | null
+  getIntField(name: string): number | null
+  getUintegerField(name: string): number | null
+  setUintegerField(name: string, value: number): void
+  getStringField(name: string): string | null
+  getBooleanField(name: string): boolean | null
+  getField(name: string, expectedType?: PointFieldType | undefined | null): boolean | number | number | number | string | null
+  setField(name: string, value: boolean | number | number | number | string, fieldType?: PointFieldType | undefined | null): void
+  setFields(values: Record<string, boolean | number | number | number | string>): void
+  removeField(name: string): void
+}
+
+/**
+ * Server side Flight SQL prepared statement, created with `InfluxDbClient.prepare`.
+ *
+ * The statement is planned once and can be executed any number of times with different
+ * parameters until `close` is called.
+ */
+export declare class PreparedStatement {
+  /**
+   * Executes the statement. `params` replace those of earlier executions, without them the
+   * statement runs without parameters.
+   */
+  query(params?: Record<string, number | bigint | boolean | string | Date | null> | undefined | null): QueryStream
+  get closed(): boolean
+  /**
+   * # Safety
+   *
+   * Releases the statement on the server, it can't be executed afterwards.
+   */
+  close(): Promise<void>
+}
+
+export interface ColumnDescription {
+  name: string
+  kind: ColumnKind
+  /** Arrow data type, e.g. `Dictionary(Int32, Utf8)` or `Float64` */
+  dataType: string
+  nullable: boolean
+}
+
+/** Role of a column in an InfluxDB table. */
+export declare enum ColumnKind {
+  Tag = 'tag',
+  Field = 'field',
+  Time = 'time'
+}
+
+export interface ColumnTransform {
+  /** Multiplies the values of a numeric column, which becomes a float64 column */
+  scale?: number
+  /** Replaces nulls of a string or tag column with empty strings, other columns are rejected */
+  nullAsEmpty?: boolean
+}
+
+/** File format written by `query_to_file`. */
+export declare enum ExportFormat {
+  Parquet = 'parquet',
+  /** CSV with a header row, timestamps as RFC3339. */
+  Csv = 'csv',
+  /** Newline-delimited JSON, one object per row. */
+  Ndjson = 'ndjson'
+}
+
+export interface ExportOptions {
+  /** Parquet compression codec. default zstd */
+  compression?: ParquetCompression
+  /** Maximum number of rows per Parquet row group. default 1048576 */
+  maxRowGroupSize?: number
+  /** Whether CSV files start with a header row. default true */
+  header?: boolean
 }
 
 export interface FlightOptions {
   keepAliveInterval?: number
   keepAliveTimeout?: number
+  /** Transport used by `query` unless the payload overrides it. default flight */
+  transport?: QueryTransport
+  /** Timestamp representation used unless the payload overrides it */
+  timestampMode?: TimestampMode
+  /** 64-bit integer representation used unless the payload overrides it. default number */
+  int64Mode?: Int64Mode
+  /** Duration, time and interval representation used unless the payload overrides it */
+  temporalMode?: TemporalMode
+  /** Null cell representation used unless the payload overrides it. default null, omit for ndjson */
+  nullMode?: NullMode
+}
+
+/**
+ * Response format requested from the HTTP query endpoints.
+ *
+ * The text formats decode integer columns as floats and `time` as a nanosecond timestamp.
+ */
+export declare enum HttpQueryFormat {
+  /** Newline-delimited JSON, decoded incrementally. */
+  Jsonl = 'jsonl',
+  /** CSV with a header row, decoded incrementally. */
+  Csv = 'csv',
+  /** Parquet keeps the exact Arrow types, but the whole response is buffered before decoding. */
+  Parquet = 'parquet'
+}
+
+/**
+ * Representation of 64-bit integers, `Date64` values and `number` timestamps in rows returned by
+ * the `library` serializer. `bigint` timestamps and `nanoseconds` durations stay `BigInt`, as
+ * their mode asks for one.
+ */
+export declare enum Int64Mode {
+  /** JS number, values above 2^53 lose precision. */
+  Number = 'number',
+  BigInt = 'bigint',
+  /** Decimal string. */
+  String = 'string',
+  /** Number when the value is a safe integer, `BigInt` otherwise. */
+  Auto = 'auto'
+}
+
+/**
+ * Representation of null cells in rows returned by the `library`, `unsafe` and `ndjson`
+ * serializers. Nulls nested in lists and structs are always `null`.
+ */
+export declare enum NullMode {
+  /** The column is left out of the row. */
+  Omit = 'omit',
+  Null = 'null',
+  /** The column is set to `undefined`, `ndjson` leaves it out like `omit`. */
+  Undefined = 'undefined'
+}
+
+/** Reshapes the record batches of a query before any serializer sees them. */
+export interface OutputMapping {
+  /** Columns to keep, in output order. default all columns */
+  columns?: Array<string>
+  /** New names by original column name, e.g. `{ time: "ts" }` */
+  rename?: Record<string, string>
+  /** Value transforms by original column name */
+  transforms?: Record<string, ColumnTransform>
 }
 
-export declare const enum PointFieldType {
+/** Compression codec of Parquet exports. */
+export declare enum ParquetCompression {
+  Uncompressed = 'uncompressed',
+  Snappy = 'snappy',
+  Gzip = 'gzip',
+  Lz4 = 'lz4',
+  Zstd = 'zstd'
+}
+
+export declare enum PointFieldType {
   Float = 'float',
   Integer = 'integer',
   UInteger = 'uinteger',
@@ -81,7 +277,7 @@ export declare const enum PointFieldType {
 export type PointFieldValue =
   | { type: 'Float', field0: number }
   | { type: 'Integer', field0: number }
-  | { type: 'UInteger', field0: number }
+  | { type: 'UInteger', field0: bigint }
   | { type: 'String', field0: string }
   | { type: 'Boolean', field0: boolean }
 
@@ -89,29 +285,100 @@ export type Precision =
   | { type: 'V2', field0: TimeUnitV2 }
   | { type: 'V3', field0: TimeUnitV3 }
 
+export interface QueryAllOptions {
+  /** Maximum number of rows to collect, the query fails once it returns more. default 100000 */
+  maxRows?: number
+}
+
 export interface QueryPayload {
   database: string
   query: string
   type?: QueryType
-  params?: Record<string, string>
+  params?: Record<string, number | bigint | boolean | string | Date | null>
+  /** Overrides the client transport for this query */
+  transport?: QueryTransport
+  /** Response format used by the HTTP transport. default jsonl */
+  format?: HttpQueryFormat
+  /**
+   * Rows of a jsonl or csv HTTP response the schema is inferred from, columns that only appear
+   * later are left out. default 1024
+   */
+  schemaInferenceRows?: number
+  /** Overrides the client timestamp representation for this query */
+  timestampMode?: TimestampMode
+  /** Overrides the client 64-bit integer representation for this query */
+  int64Mode?: Int64Mode
+  /** Overrides the client duration, time and interval representation for this query */
+  temporalMode?: TemporalMode
+  /** Overrides the client null cell representation for this query */
+  nullMode?: NullMode
+  /** Projection, renames and transforms applied to the result before it is serialized */
+  output?: OutputMapping
 }
 
-export declare const enum QueryType {
+/** Transport used to run queries. */
+export declare enum QueryTransport {
+  /** Arrow Flight over gRPC (HTTP/2). */
+  Flight = 'flight',
+  /** Plain HTTP requests to `/api/v3/query_sql` and `/api/v3/query_influxql`. */
+  Http = 'http'
+}
+
+export declare enum QueryType {
   Sql = 'sql',
   InfluxQl = 'influxql',
+  /** Standard Flight SQL (`CommandStatementQuery`), usable with any Flight SQL server. */
   FlightSql = 'flight_sql'
 }
 
 export type ReturnDataType =
   Record<string, any | undefined | null>
 
-export declare const enum Serializer {
-  Unsafe = 0
+export declare enum Serializer {
+  Unsafe = 'unsafe',
+  Library = 'library',
+  Raw = 'raw',
+  Columnar = 'columnar',
+  /** Arrow IPC stream: the schema once, then a chunk per record batch. */
+  RawStream = 'raw_stream',
+  /** Arrow IPC file, emitted as a single buffer once the query completed. */
+  RawFile = 'raw_file',
+  /** CSV text chunks, the header row first. */
+  Csv = 'csv',
+  /** Newline-delimited JSON text chunks. */
+  Ndjson = 'ndjson'
+}
+
+export interface TableDescription {
+  database: string
+  table: string
+  columns: Array<ColumnDescription>
+}
+
+/**
+ * Representation of durations, times of day and intervals in rows returned by the `library`
+ * serializer. Intervals are `{ months, days, nanoseconds }` objects in both modes.
+ */
+export declare enum TemporalMode {
+  /** Durations as `{ value, unit }` objects, times of day as `HH:MM:SS.fffffffff` strings. */
+  Structured = 'structured',
+  /** Durations and times of day as `BigInt` nanoseconds, whatever the `Int64Mode`. */
+  Nanoseconds = 'nanoseconds'
 }
 
-export declare function test(): Promise<void>
+/** Representation of timestamp values in rows returned by the `library` serializer. */
+export declare enum TimestampMode {
+  /** JS `Date`, truncated to milliseconds. */
+  Date = 'date',
+  /** `BigInt` nanoseconds since the Unix epoch, whatever the `Int64Mode`. */
+  BigInt = 'bigint',
+  /** Number of milliseconds since the Unix epoch. */
+  Number = 'number',
+  /** RFC3339 string in the timezone of the column, UTC when it has none. */
+  String = 'string'
+}
 
-export declare const enum TimeUnitV2 {
+export declare enum TimeUnitV2 {
   /** Time in seconds. */
   Second = 's',
   /** Time in milliseconds. */
@@ -122,7 +389,7 @@ export declare const enum TimeUnitV2 {
   Nanosecond = 'ns'
 }
 
-export declare const enum TimeUnitV3 {
+export declare enum TimeUnitV3 {
   /** Time in seconds. */
   Second = 'second',
   /** Time in milliseconds. */
@@ -140,14 +407,16 @@ export interface WriteOptions {
   headers?: Record<string, string>
   /** When specified, write bodies larger than the threshold are gzipped */
   gzip: boolean
-  /** * Instructs the server whether to wait with the response until WAL persistence completes.
+  /**
+   * Instructs the server whether to wait with the response until WAL persistence completes.
    * noSync=true means faster write but without the confirmation that the data was persisted.
    *
    * Note: This option is supported by InfluxDB 3 Core and Enterprise servers only.
    * For other InfluxDB 3 server types (InfluxDB Clustered, InfluxDB Clould Serverless/Dedicated)
    * the write operation will fail with an error.
    *
-   * Default value: false. */
+   * Default value: false.
+   */
   noSync?: boolean
   defaultTags?: Record<string, string>
 }
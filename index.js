@@ -397,9 +397,18 @@ module.exports.InfluxDbClient = nativeBinding.InfluxDbClient
 module.exports.InfluxDBClient = nativeBinding.InfluxDBClient
 module.exports.Point = nativeBinding.Point
 module.exports.PointValues = nativeBinding.PointValues
+module.exports.PreparedStatement = nativeBinding.PreparedStatement
+module.exports.ColumnKind = nativeBinding.ColumnKind
+module.exports.ExportFormat = nativeBinding.ExportFormat
+module.exports.HttpQueryFormat = nativeBinding.HttpQueryFormat
+module.exports.Int64Mode = nativeBinding.Int64Mode
+module.exports.NullMode = nativeBinding.NullMode
+module.exports.ParquetCompression = nativeBinding.ParquetCompression
 module.exports.PointFieldType = nativeBinding.PointFieldType
+module.exports.QueryTransport = nativeBinding.QueryTransport
 module.exports.QueryType = nativeBinding.QueryType
 module.exports.Serializer = nativeBinding.Serializer
-module.exports.test = nativeBinding.test
+module.exports.TemporalMode = nativeBinding.TemporalMode
+module.exports.TimestampMode = nativeBinding.TimestampMode
 module.exports.TimeUnitV2 = nativeBinding.TimeUnitV2
 module.exports.TimeUnitV3 = nativeBinding.TimeUnitV3
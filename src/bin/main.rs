@@ -1,5 +1,3 @@
-use napi::tokio;
-
 #[tokio::main]
 #[cfg(feature = "native")]
 async fn main() {
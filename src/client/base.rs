@@ -1,37 +1,154 @@
-// CURRENTLY NAPI-RS DOES NOT SUPPORT TRAIT IMPLEMENTATIONS
-
-// use napi::bindgen_prelude::{Either3, ReadableStream};
-// use napi::Env;
-// use crate::client::options::{FlightOptions, QueryPayload};
-// use crate::client::WriteOptions;
-// use crate::serializer::library_serializer::LibraryReturnType;
-// use crate::serializer::Serializer;
-// 
-// pub trait InfluxClientTrait {
-//     fn new(
-//         addr: String,
-//         token: Option<String>,
-//         serializer: Option<Serializer>,
-//         options: Option<FlightOptions>,
-//     ) -> Self;
-// 
-//     fn query(
-//         &mut self,
-//         query_payload: QueryPayload,
-//         env: &Env,
-//     ) -> napi::Result<
-//         Either3<
-//             ReadableStream<'_, LibraryReturnType>,
-//             ReadableStream<'_, serde_json::Map<String, serde_json::Value>>,
-//             ReadableStream<'_, napi::bindgen_prelude::Buffer>,
-//         >,
-//     >;
-// 
-//     fn write(
-//         &mut self,
-//         lines: Vec<String>,
-//         database: String,
-//         write_options: Option<WriteOptions>,
-//         org: Option<String>,
-//     ) -> napi::Result<()>;
-// }
+use crate::client::http_client::get_http_client;
+use crate::client::options::{to_header_map, QueryPayload, QueryType, WriteOptions};
+use crate::error::{Error, Result};
+use crate::point::typed::{to_lines, InfluxPoint};
+use crate::write::get_write_path;
+use reqwest::{Client, Response, Url};
+use serde_json::json;
+
+/// Address, authentication, writes and HTTP queries, shared by every client front end.
+///
+/// The native and JS clients reach it through [`CoreClient`](crate::client::core::CoreClient),
+/// the browser client, which has no Flight transport, uses it directly.
+#[derive(Clone)]
+pub struct HttpCore {
+  addr: String,
+  http_client: Client,
+}
+
+impl HttpCore {
+  pub fn new(addr: String, token: Option<String>) -> Self {
+    Self {
+      http_client: get_http_client(token.unwrap_or_default()),
+      addr,
+    }
+  }
+
+  pub fn addr(&self) -> &str {
+    &self.addr
+  }
+
+  /// HTTP client sending the token with every request.
+  pub(crate) fn http_client(&self) -> &Client {
+    &self.http_client
+  }
+
+  /// Sends the query to the HTTP query API and returns the successful response, whose body is
+  /// in the format of the payload.
+  pub(crate) async fn query(&self, query_payload: QueryPayload) -> Result<Response> {
+    let url = get_query_path(&self.addr, query_payload._type.as_ref())?;
+    let format = query_payload.format.unwrap_or_default();
+
+    let body = match query_payload.params {
+      Some(params) => json!({
+          "db": query_payload.database,
+          "q": query_payload.query,
+          "format": format.str(),
+          "params": params
+      }),
+      None => json!({
+          "db": query_payload.database,
+          "q": query_payload.query,
+          "format": format.str(),
+      }),
+    };
+
+    let response = self
+      .http_client
+      .post(url)
+      .json(&body)
+      .send()
+      .await
+      .map_err(|e| Error::Transport(format!("HTTP query request failed: {e}")))?;
+
+    match response.status() {
+      status if status.is_success() => Ok(response),
+      status => Err(Error::from_response(
+        status,
+        response.text().await.unwrap_or_default(),
+      )),
+    }
+  }
+
+  /// Writes line protocol `lines` to `database`.
+  pub async fn write(
+    &self,
+    lines: Vec<String>,
+    database: String,
+    write_options: Option<WriteOptions>,
+    org: Option<String>,
+  ) -> Result<()> {
+    let (url, write_options) = get_write_path(&self.addr, database, org, write_options)?;
+    let headers = to_header_map(&write_options.headers.unwrap_or_default())
+      .map_err(|e| Error::Request(format!("Invalid write header: {e}")))?;
+
+    let response = self
+      .http_client
+      .post(url)
+      .body(lines.join("\n"))
+      .headers(headers)
+      .send()
+      .await
+      .map_err(|e| Error::Transport(format!("Write request failed: {e}")))?;
+
+    match response.status() {
+      status if status.is_success() => Ok(()),
+      status => Err(Error::from_response(
+        status,
+        response.text().await.unwrap_or_default(),
+      )),
+    }
+  }
+
+  /// Writes Rust values as points, e.g. structs deriving `InfluxPoint`.
+  pub async fn write_points<P: InfluxPoint>(
+    &self,
+    points: &[P],
+    database: String,
+    write_options: Option<WriteOptions>,
+    org: Option<String>,
+  ) -> Result<()> {
+    let lines = to_lines(points, write_options.as_ref());
+    self.write(lines, database, write_options, org).await
+  }
+}
+
+pub fn get_query_path(url: &str, query_type: Option<&QueryType>) -> Result<Url> {
+  let query_path = match query_type.unwrap_or(&QueryType::Sql) {
+    QueryType::Sql => QUERY_SQL_PATH,
+    QueryType::InfluxQl => QUERY_INFLUXQL_PATH,
+    QueryType::FlightSql => {
+      return Err(Error::Request(
+        "Flight SQL queries are not supported by the HTTP transport".to_string(),
+      ))
+    }
+  };
+
+  Url::parse(format!("{url}{query_path}").as_str())
+    .map_err(|e| Error::Request(format!("Error parsing URL: {e}")))
+}
+
+static QUERY_SQL_PATH: &str = "/api/v3/query_sql";
+static QUERY_INFLUXQL_PATH: &str = "/api/v3/query_influxql";
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_query_path() {
+    assert_eq!(
+      get_query_path("http://localhost:8181", None)
+        .unwrap()
+        .as_str(),
+      "http://localhost:8181/api/v3/query_sql"
+    );
+    assert_eq!(
+      get_query_path("http://localhost:8181", Some(&QueryType::InfluxQl))
+        .unwrap()
+        .as_str(),
+      "http://localhost:8181/api/v3/query_influxql"
+    );
+    assert!(get_query_path("http://localhost:8181", Some(&QueryType::FlightSql)).is_err());
+  }
+}
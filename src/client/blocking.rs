@@ -0,0 +1,157 @@
+use crate::client::core::CoreClient;
+use crate::client::options::{
+  ExportFormat, ExportOptions, FlightOptions, QueryPayload, WriteOptions,
+};
+use crate::error::{Error, Result};
+use crate::point::typed::InfluxPoint;
+use crate::query::common::metadata::TableDescription;
+use arrow::array::RecordBatch;
+use futures_util::stream::BoxStream;
+use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// Row returned by [`InfluxDBClient::query`].
+pub type Row = serde_json::Map<String, serde_json::Value>;
+
+/// Synchronous client for CLIs and batch jobs, running [`CoreClient`] on its own runtime.
+///
+/// Like `reqwest::blocking`, it must not be used from within an async runtime.
+pub struct InfluxDBClient {
+  runtime: Arc<Runtime>,
+  core: CoreClient,
+}
+
+impl InfluxDBClient {
+  /// Creates the client, connecting the Flight channel unless queries use HTTP.
+  pub fn new(addr: String, token: Option<String>, options: Option<FlightOptions>) -> Result<Self> {
+    let runtime = Builder::new_multi_thread()
+      .enable_all()
+      .build()
+      .map_err(|e| Error::Request(format!("Failed to start the runtime: {e}")))?;
+    let core = runtime.block_on(CoreClient::connect(addr, token, options))?;
+
+    Ok(Self {
+      runtime: Arc::new(runtime),
+      core,
+    })
+  }
+
+  /// Runs the query and iterates over its rows as JSON objects.
+  pub fn query(&mut self, query_payload: QueryPayload) -> Result<QueryIter<Row>> {
+    self.query_as(query_payload)
+  }
+
+  /// Runs the query and iterates over its rows deserialized into `T`.
+  pub fn query_as<T>(&mut self, query_payload: QueryPayload) -> Result<QueryIter<T>>
+  where
+    T: DeserializeOwned + Send + 'static,
+  {
+    let stream = self.runtime.block_on(self.core.query_as(query_payload))?;
+    Ok(self.iter(stream))
+  }
+
+  /// Runs the query and iterates over its record batches.
+  pub fn query_batches(&mut self, query_payload: QueryPayload) -> Result<QueryIter<RecordBatch>> {
+    let stream = self
+      .runtime
+      .block_on(self.core.query_batches(query_payload))?;
+    Ok(self.iter(stream))
+  }
+
+  /// Runs the query and writes the result to `path`. Returns the number of rows written.
+  pub fn query_to_file(
+    &mut self,
+    query_payload: QueryPayload,
+    path: &str,
+    format: ExportFormat,
+    options: Option<ExportOptions>,
+  ) -> Result<i64> {
+    self.runtime.block_on(
+      self
+        .core
+        .query_to_file(query_payload, path, format, options),
+    )
+  }
+
+  /// Writes line protocol `lines` to `database`.
+  pub fn write(
+    &self,
+    lines: Vec<String>,
+    database: String,
+    write_options: Option<WriteOptions>,
+    org: Option<String>,
+  ) -> Result<()> {
+    self
+      .runtime
+      .block_on(self.core.write(lines, database, write_options, org))
+  }
+
+  /// Writes Rust values as points, e.g. structs deriving `InfluxPoint`.
+  pub fn write_points<P: InfluxPoint>(
+    &self,
+    points: &[P],
+    database: String,
+    write_options: Option<WriteOptions>,
+    org: Option<String>,
+  ) -> Result<()> {
+    self
+      .runtime
+      .block_on(self.core.write_points(points, database, write_options, org))
+  }
+
+  /// Lists the databases of an InfluxDB 3 Core or Enterprise server, other servers fail.
+  pub fn list_databases(&self) -> Result<Vec<String>> {
+    self.runtime.block_on(self.core.list_databases())
+  }
+
+  /// Lists the tables of `database`, without the system tables of the server.
+  pub fn list_tables(&self, database: &str) -> Result<Vec<String>> {
+    self.runtime.block_on(self.core.list_tables(database))
+  }
+
+  /// Returns the columns of `table` with their role (tag, field or time) and Arrow type.
+  pub fn describe_table(&self, database: &str, table: &str) -> Result<TableDescription> {
+    self
+      .runtime
+      .block_on(self.core.describe_table(database, table))
+  }
+
+  fn iter<T>(&self, stream: BoxStream<'static, Result<T>>) -> QueryIter<T> {
+    QueryIter {
+      runtime: self.runtime.clone(),
+      stream,
+    }
+  }
+}
+
+/// Items of a query, each one fetched by blocking on the runtime of the client.
+///
+/// The iterator keeps the runtime alive, so it can outlive the client.
+pub struct QueryIter<T> {
+  runtime: Arc<Runtime>,
+  stream: BoxStream<'static, Result<T>>,
+}
+
+impl<T> Iterator for QueryIter<T> {
+  type Item = Result<T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.runtime.block_on(self.stream.next())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_query_iter() {
+    let runtime = Arc::new(Runtime::new().unwrap());
+    let stream = futures_util::stream::iter(vec![Ok(1), Err(Error::Unauthorized)]).boxed();
+    let items: Vec<_> = QueryIter { runtime, stream }.collect();
+
+    assert_eq!(items, vec![Ok(1), Err(Error::Unauthorized)]);
+  }
+}
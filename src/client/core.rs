@@ -0,0 +1,241 @@
+use crate::client::base::HttpCore;
+use crate::client::channel::get_channel;
+use crate::client::options::{
+  ExportFormat, ExportOptions, FlightOptions, QueryPayload, QueryTransport, QueryType, WriteOptions,
+};
+use crate::error::{Error, Result};
+use crate::point::typed::InfluxPoint;
+use crate::query::common::export::write_to_file;
+use crate::query::common::flight_sql::{prepare_statement, query_flight_sql};
+use crate::query::common::http_query::query_http;
+use crate::query::common::metadata::{self, TableDescription};
+use crate::query::common::output_mapping::map_output;
+use crate::query::common::query_processor::{into_typed_stream, RecordBatchStream};
+use arrow::array::RecordBatch;
+use arrow_flight::decode::FlightRecordBatchStream;
+use arrow_flight::sql::client::{
+  FlightSqlServiceClient, PreparedStatement as FlightPreparedStatement,
+};
+use arrow_flight::{FlightClient, Ticket};
+use futures_util::stream::BoxStream;
+use futures_util::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use tonic::codegen::Bytes;
+use tonic::transport::Channel;
+
+/// InfluxDB 3 client with plain Rust types, shared by the native and JS clients.
+///
+/// It owns the Flight connection, queries and the management calls on top of an [`HttpCore`],
+/// which handles authentication and writes. The front ends only add their serializers.
+pub struct CoreClient {
+  http: HttpCore,
+  flight_client: FlightClient,
+  flight_sql_client: FlightSqlServiceClient<Channel>,
+  transport: QueryTransport,
+}
+
+impl CoreClient {
+  /// Creates a client whose Flight channel connects on first use.
+  pub fn new(addr: String, token: Option<String>, options: Option<FlightOptions>) -> Self {
+    let transport = transport(options.as_ref());
+    let channel = get_channel(addr.clone(), options).connect_lazy();
+
+    Self::with_channel(addr, token, channel, transport)
+  }
+
+  /// Creates a client and connects the Flight channel right away, unless queries use HTTP.
+  pub async fn connect(
+    addr: String,
+    token: Option<String>,
+    options: Option<FlightOptions>,
+  ) -> Result<Self> {
+    let transport = transport(options.as_ref());
+    let endpoint = get_channel(addr.clone(), options);
+
+    // Environments that need the HTTP transport usually can't reach the Flight endpoint,
+    // so the channel is only connected on first use there.
+    let channel = match transport {
+      QueryTransport::Flight => endpoint
+        .connect()
+        .await
+        .map_err(|e| Error::Transport(format!("Failed to connect to {addr}: {e}")))?,
+      QueryTransport::Http => endpoint.connect_lazy(),
+    };
+
+    Ok(Self::with_channel(addr, token, channel, transport))
+  }
+
+  fn with_channel(
+    addr: String,
+    token: Option<String>,
+    channel: Channel,
+    transport: QueryTransport,
+  ) -> Self {
+    let http = HttpCore::new(addr, token.clone());
+
+    let mut flight_client = FlightClient::new(channel.clone());
+    let mut flight_sql_client = FlightSqlServiceClient::new(channel);
+
+    if let Some(token) = token {
+      flight_client
+        .add_header("authorization", format!("Bearer {token}").as_str())
+        .unwrap();
+      flight_sql_client.set_token(token);
+    }
+
+    Self {
+      http,
+      flight_client,
+      flight_sql_client,
+      transport,
+    }
+  }
+
+  /// Runs the query and returns its record batches as they arrive.
+  pub async fn query_batches(
+    &mut self,
+    query_payload: QueryPayload,
+  ) -> Result<BoxStream<'static, Result<RecordBatch>>> {
+    let response = self.record_batch_stream(query_payload).await?;
+
+    Ok(response)
+  }
+
+  /// Runs the query and deserializes every row into `T`, matching columns to fields by name.
+  pub async fn query_as<T>(
+    &mut self,
+    query_payload: QueryPayload,
+  ) -> Result<BoxStream<'static, Result<T>>>
+  where
+    T: DeserializeOwned + Send + 'static,
+  {
+    let response = self.record_batch_stream(query_payload).await?;
+
+    Ok(into_typed_stream(response).boxed())
+  }
+
+  /// Runs the query and writes the result to `path`. Returns the number of rows written.
+  pub async fn query_to_file(
+    &mut self,
+    query_payload: QueryPayload,
+    path: &str,
+    format: ExportFormat,
+    options: Option<ExportOptions>,
+  ) -> Result<i64> {
+    let response = self.record_batch_stream(query_payload).await?;
+    write_to_file(response, path, format, options.unwrap_or_default()).await
+  }
+
+  /// Record batches of the query with its output mapping applied, fetched with the transport
+  /// of the payload or of the client.
+  pub(crate) async fn record_batch_stream(
+    &mut self,
+    mut query_payload: QueryPayload,
+  ) -> Result<RecordBatchStream> {
+    let output = query_payload.output.take();
+
+    let response: RecordBatchStream = match query_payload.transport.unwrap_or(self.transport) {
+      QueryTransport::Flight if matches!(query_payload._type, Some(QueryType::FlightSql)) => {
+        let response =
+          query_flight_sql(&self.flight_sql_client, self.http.addr(), query_payload).await?;
+        Box::pin(response)
+      }
+      QueryTransport::Flight => {
+        let payload: String = query_payload.into();
+
+        let ticket = Ticket {
+          ticket: Bytes::from(payload),
+        };
+
+        let response = self.flight_client.do_get(ticket).await?;
+        Box::pin(flight_batches(response))
+      }
+      QueryTransport::Http => {
+        let response = query_http(&self.http, query_payload).await?;
+        Box::pin(response)
+      }
+    };
+
+    Ok(map_output(response, output))
+  }
+
+  /// Prepares `query` on `database` with Flight SQL.
+  pub(crate) async fn prepare(
+    &self,
+    database: &str,
+    query: String,
+  ) -> Result<(
+    FlightSqlServiceClient<Channel>,
+    FlightPreparedStatement<Channel>,
+  )> {
+    prepare_statement(&self.flight_sql_client, database, query).await
+  }
+
+  /// Writes line protocol `lines` to `database`.
+  pub async fn write(
+    &self,
+    lines: Vec<String>,
+    database: String,
+    write_options: Option<WriteOptions>,
+    org: Option<String>,
+  ) -> Result<()> {
+    self.http.write(lines, database, write_options, org).await
+  }
+
+  /// Writes Rust values as points, e.g. structs deriving `InfluxPoint`.
+  pub async fn write_points<P: InfluxPoint>(
+    &self,
+    points: &[P],
+    database: String,
+    write_options: Option<WriteOptions>,
+    org: Option<String>,
+  ) -> Result<()> {
+    self
+      .http
+      .write_points(points, database, write_options, org)
+      .await
+  }
+
+  pub fn http(&self) -> &HttpCore {
+    &self.http
+  }
+
+  /// Lists the databases of an InfluxDB 3 Core or Enterprise server, other servers fail.
+  pub async fn list_databases(&self) -> Result<Vec<String>> {
+    metadata::list_databases(self.http.http_client(), self.http.addr()).await
+  }
+
+  /// Lists the tables of `database`, without the system tables of the server.
+  pub async fn list_tables(&self, database: &str) -> Result<Vec<String>> {
+    metadata::list_tables(&self.flight_sql_client, self.http.addr(), database).await
+  }
+
+  /// Returns the columns of `table` with their role (tag, field or time) and Arrow type.
+  pub async fn describe_table(&self, database: &str, table: &str) -> Result<TableDescription> {
+    metadata::describe_table(&self.flight_sql_client, self.http.addr(), database, table).await
+  }
+}
+
+/// Batches of a Flight response. A response without any batch yields an empty one with the
+/// schema of the response, so exports and Arrow IPC output keep the columns of an empty result.
+fn flight_batches(
+  response: FlightRecordBatchStream,
+) -> impl Stream<Item = Result<RecordBatch>> + Send + 'static {
+  futures_util::stream::unfold(Some((response, false)), |state| async move {
+    let (mut response, received) = state?;
+    match response.next().await {
+      Some(batch) => Some((batch.map_err(Error::from), Some((response, true)))),
+      None if received => None,
+      None => {
+        let schema = response.schema()?.clone();
+        Some((Ok(RecordBatch::new_empty(schema)), None))
+      }
+    }
+  })
+}
+
+fn transport(options: Option<&FlightOptions>) -> QueryTransport {
+  options
+    .and_then(|options| options.transport)
+    .unwrap_or_default()
+}
@@ -1,15 +1,22 @@
 #[cfg(not(target_arch = "wasm32"))]
 pub mod channel;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod core;
+
 #[cfg(feature = "native")]
 pub mod native;
 
+#[cfg(feature = "native")]
+pub mod blocking;
+
 #[cfg(target_arch = "wasm32")]
 pub mod browser;
 
 #[cfg(not(target_arch = "wasm32"))]
-#[cfg(not(feature = "native"))]
+#[cfg(all(feature = "napi", not(feature = "native")))]
 pub mod napi_rs;
 
+pub mod base;
 pub mod http_client;
 pub mod options;
@@ -1,28 +1,25 @@
-use crate::client::channel::get_channel;
-use crate::client::http_client::get_http_client;
+use crate::client::core::CoreClient;
+use crate::client::napi_rs::prepared_statement::PreparedStatement;
+use crate::client::napi_rs::{js_rows, js_stream, readable_stream, JsQueryRows, JsQueryStream};
 pub use crate::client::options::{to_header_map, WriteOptions};
-use crate::client::options::{FlightOptions, QueryPayload};
-use crate::query::common::query_processor::into_stream;
-use crate::serializer::common::library_serializer::{LibraryReturnType, LibrarySerializer};
-use crate::serializer::common::raw_serializer::RawSerializer;
-use crate::serializer::common::unsafe_serializer::UnsafeSerializer;
-use crate::serializer::common::Serializer;
-use crate::serializer::common::SerializerTrait;
-use crate::write::get_write_path;
-use crate::Status;
-use arrow_flight::{FlightClient, Ticket};
+use crate::client::options::{
+  ExportFormat, ExportOptions, FlightOptions, QueryAllOptions, QueryPayload,
+  DEFAULT_QUERY_ALL_MAX_ROWS,
+};
+use crate::query::common::metadata::TableDescription;
+use crate::query::common::query_processor::{
+  collect_batches, into_serializer_stream, serialize_rows, serialize_stream,
+};
+use crate::serializer::common::registry::{registered_serializer, RegisteredOutput};
+use crate::serializer::common::{SerializeOptions, Serializer};
 use napi::bindgen_prelude::*;
-use napi::tokio_stream::wrappers::ReceiverStream;
 use napi::Env;
-use reqwest::Client;
-use tonic::codegen::Bytes;
 
 #[napi_derive::napi]
 pub struct InfluxDBClient {
-  addr: String,
-  flight_client: FlightClient,
+  core: CoreClient,
   serializer: Serializer,
-  http_client: Client,
+  serialize_options: SerializeOptions,
 }
 
 #[napi_derive::napi]
@@ -35,79 +32,125 @@ impl InfluxDBClient {
     options: Option<FlightOptions>,
   ) -> Self {
     use napi::bindgen_prelude::block_on;
-    let channel = block_on(async {
-      get_channel(addr.clone(), options)
-        .connect()
-        .await
-        .expect("error connecting")
-    });
-
-    let http_client = get_http_client(token.clone().unwrap_or(String::from("")));
-
-    let mut flight_client = FlightClient::new(channel);
-
-    if let Some(token) = token {
-      flight_client
-        .add_header("authorization", format!("Bearer {token}").as_str())
-        .unwrap();
-    }
+    let serialize_options = SerializeOptions::new(options.as_ref());
+    let core = block_on(CoreClient::connect(addr, token, options)).expect("error connecting");
 
     Self {
-      addr,
-      flight_client,
-      http_client,
+      core,
       serializer: serializer.unwrap_or(Serializer::Unsafe),
+      serialize_options,
     }
   }
 
-  #[allow(clippy::type_complexity)]
-  #[napi_derive::napi]
+  #[napi_derive::napi(ts_return_type = "QueryStream")]
   pub fn query(
     &mut self,
     query_payload: QueryPayload,
     env: &Env,
-  ) -> napi::Result<
-    Either3<
-      ReadableStream<'_, LibraryReturnType>,
-      ReadableStream<'_, serde_json::Map<String, serde_json::Value>>,
-      ReadableStream<'_, Buffer>,
-    >,
-  > {
-    match self.serializer {
-      Serializer::Library => {
-        let stream = self.query_inner::<LibrarySerializer>(query_payload, env)?;
-        Ok(Either3::A(stream))
-      }
-      Serializer::Unsafe => {
-        let stream = self.query_inner::<UnsafeSerializer>(query_payload, env)?;
-        Ok(Either3::B(stream))
-      }
-      Serializer::Raw => {
-        let stream = self.query_inner::<RawSerializer>(query_payload, env)?;
-        Ok(Either3::C(stream))
-      }
-    }
+  ) -> napi::Result<JsQueryStream<'_>> {
+    use napi::bindgen_prelude::block_on;
+
+    let options = self.serialize_options.for_query(&query_payload);
+    let stream = block_on(async {
+      let response = self.core.record_batch_stream(query_payload).await?;
+      Ok::<_, napi::Error>(serialize_stream(response, self.serializer.clone(), options))
+    })?;
+
+    readable_stream(env, stream)
   }
 
-  pub fn query_inner<S: SerializerTrait>(
+  /// Runs the query with a serializer that a native addon registered under `serializer` with
+  /// `register_serializer`.
+  #[napi_derive::napi(ts_return_type = "ReadableStream<any>")]
+  pub fn query_with(
     &mut self,
+    serializer: String,
     query_payload: QueryPayload,
     env: &Env,
-  ) -> napi::Result<ReadableStream<'_, S::Output>> {
-    let payload: String = query_payload.into();
+  ) -> napi::Result<ReadableStream<'_, RegisteredOutput>> {
+    use napi::bindgen_prelude::block_on;
 
-    let ticket = Ticket {
-      ticket: Bytes::from(payload),
-    };
+    let serializer = registered_serializer(&serializer)?;
+    let options = self.serialize_options.for_query(&query_payload);
+    let stream = block_on(async {
+      let response = self.core.record_batch_stream(query_payload).await?;
+      Ok::<_, napi::Error>(into_serializer_stream(response, serializer, options))
+    })?;
 
+    ReadableStream::new(env, js_stream(stream))
+  }
+
+  /// Runs the query and collects every row into a single array, or into a single Arrow IPC
+  /// buffer for the `raw` serializer. Fails once more than `maxRows` rows are returned.
+  #[napi_derive::napi(ts_return_type = "Promise<QueryRows>")]
+  pub async unsafe fn query_all(
+    &mut self,
+    query_payload: QueryPayload,
+    options: Option<QueryAllOptions>,
+  ) -> Result<JsQueryRows> {
+    let max_rows = options
+      .and_then(|options| options.max_rows)
+      .unwrap_or(DEFAULT_QUERY_ALL_MAX_ROWS);
+
+    let serialize_options = self.serialize_options.for_query(&query_payload);
+    let response = self.core.record_batch_stream(query_payload).await?;
+    let batches = collect_batches(response, max_rows).await?;
+
+    let rows = serialize_rows(batches, self.serializer.clone(), &serialize_options).await?;
+    Ok(js_rows(rows))
+  }
+
+  /// Runs the query and writes the result to `path` without handing rows to JS.
+  /// Returns the number of rows written.
+  #[napi_derive::napi]
+  pub async unsafe fn query_to_file(
+    &mut self,
+    query_payload: QueryPayload,
+    path: String,
+    format: ExportFormat,
+    options: Option<ExportOptions>,
+  ) -> Result<i64> {
+    Ok(
+      self
+        .core
+        .query_to_file(query_payload, &path, format, options)
+        .await?,
+    )
+  }
+
+  /// Prepares `query` on `database` with Flight SQL, so it is planned once and can be executed
+  /// repeatedly with different parameters.
+  #[napi_derive::napi]
+  pub fn prepare(&mut self, database: String, query: String) -> napi::Result<PreparedStatement> {
     use napi::bindgen_prelude::block_on;
 
-    let stream: ReceiverStream<Result<<S as SerializerTrait>::Output>> = block_on(async {
-      let response = self.flight_client.do_get(ticket).await;
-      into_stream::<S>(response.unwrap())
-    });
+    let (client, statement) = block_on(async { self.core.prepare(&database, query).await })?;
+
+    Ok(PreparedStatement::new(
+      client,
+      self.core.http().addr().to_string(),
+      statement,
+      self.serializer.clone(),
+      self.serialize_options.clone(),
+    ))
+  }
+
+  /// Lists the databases of an InfluxDB 3 Core or Enterprise server, other servers fail.
+  #[napi_derive::napi]
+  pub async fn list_databases(&self) -> Result<Vec<String>> {
+    Ok(self.core.list_databases().await?)
+  }
 
-    ReadableStream::new(env, stream)
+  /// Lists the tables of `database`, without the system tables of the server.
+  #[napi_derive::napi]
+  pub async fn list_tables(&self, database: String) -> Result<Vec<String>> {
+    Ok(self.core.list_tables(&database).await?)
+  }
+
+  /// Returns the columns of `table` with their role (tag, field or time) and Arrow type.
+  #[napi_derive::napi]
+  pub async fn describe_table(&self, database: String, table: String) -> Result<TableDescription> {
+    Ok(self.core.describe_table(&database, &table).await?)
   }
 
   #[napi_derive::napi]
@@ -121,42 +164,6 @@ impl InfluxDBClient {
     write_options: Option<WriteOptions>,
     org: Option<String>,
   ) -> Result<()> {
-    self.write_inner(lines, database, write_options, org).await
-  }
-
-  async fn write_inner(
-    &mut self,
-    lines: Vec<String>,
-    database: String,
-    write_options: Option<WriteOptions>,
-    org: Option<String>,
-  ) -> napi::Result<()> {
-    let (url, write_options) = get_write_path(&self.addr, database, org, write_options)?;
-
-    let headers = to_header_map(&write_options.headers.unwrap_or_default()).unwrap();
-    let response = &self
-      .http_client
-      .post(url)
-      .body(lines.join("\n"))
-      .headers(headers)
-      .send()
-      .await;
-
-    if let Err(e) = response {
-      match e.status() {
-        Some(reqwest::StatusCode::UNAUTHORIZED) => Err(napi::Error::from_reason("Unauthorized")),
-        _ => Err(napi::Error::from_status(Status::Cancelled)),
-      }
-    } else {
-      match response {
-        Ok(response) => match response.status() {
-          reqwest::StatusCode::OK => Ok(()),
-          reqwest::StatusCode::NO_CONTENT => Ok(()),
-          reqwest::StatusCode::UNAUTHORIZED => Err(napi::Error::from_reason("Unauthorized")),
-          _ => Err(napi::Error::from_reason("Unknown")),
-        },
-        Err(_error) => Err(napi::Error::from_status(Status::Cancelled)),
-      }
-    }
+    Ok(self.core.write(lines, database, write_options, org).await?)
   }
 }
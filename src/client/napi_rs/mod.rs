@@ -1,2 +1,59 @@
 #[cfg(not(feature = "native"))]
 pub mod client;
+#[cfg(not(feature = "native"))]
+pub mod prepared_statement;
+
+use crate::error::Result;
+use crate::query::common::query_processor::{QueryRows, QueryStream};
+use crate::serializer::common::columnar_serializer::ColumnarBatch;
+use crate::serializer::common::library_serializer::LibraryReturnType;
+use crate::serializer::common::unsafe_serializer::JsonRow;
+use futures_util::{Stream, StreamExt};
+use napi::bindgen_prelude::{Buffer, Either4, ReadableStream};
+use napi::Env;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// JS stream of a query, `QueryStream` in TS.
+pub(crate) type JsQueryStream<'env> = Either4<
+  ReadableStream<'env, LibraryReturnType>,
+  ReadableStream<'env, JsonRow>,
+  ReadableStream<'env, Buffer>,
+  ReadableStream<'env, ColumnarBatch>,
+>;
+
+/// Rows of a whole query, `QueryRows` in TS.
+pub(crate) type JsQueryRows =
+  Either4<Vec<LibraryReturnType>, Vec<JsonRow>, Buffer, Vec<ColumnarBatch>>;
+
+/// Converts a stream of the Rust client into items a JS `ReadableStream` can take.
+pub(crate) fn js_stream<T, U>(
+  stream: ReceiverStream<Result<T>>,
+) -> impl Stream<Item = napi::Result<U>> + Unpin + Send + 'static
+where
+  T: Send + 'static,
+  U: From<T> + Send + 'static,
+{
+  stream.map(|item| item.map(U::from).map_err(napi::Error::from))
+}
+
+/// Wraps the stream of a query into a JS `ReadableStream` of its serializer output.
+pub(crate) fn readable_stream<'env>(
+  env: &Env,
+  stream: QueryStream,
+) -> napi::Result<JsQueryStream<'env>> {
+  Ok(match stream {
+    QueryStream::Library(stream) => Either4::A(ReadableStream::new(env, js_stream(stream))?),
+    QueryStream::Unsafe(stream) => Either4::B(ReadableStream::new(env, js_stream(stream))?),
+    QueryStream::Bytes(stream) => Either4::C(ReadableStream::new(env, js_stream(stream))?),
+    QueryStream::Columnar(stream) => Either4::D(ReadableStream::new(env, js_stream(stream))?),
+  })
+}
+
+pub(crate) fn js_rows(rows: QueryRows) -> JsQueryRows {
+  match rows {
+    QueryRows::Library(rows) => Either4::A(rows),
+    QueryRows::Unsafe(rows) => Either4::B(rows),
+    QueryRows::Bytes(bytes) => Either4::C(bytes.into()),
+    QueryRows::Columnar(batches) => Either4::D(batches),
+  }
+}
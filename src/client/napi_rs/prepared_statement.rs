@@ -0,0 +1,91 @@
+use crate::client::napi_rs::{readable_stream, JsQueryStream};
+use crate::client::options::QueryParamValue;
+use crate::query::common::flight_sql::execute_prepared;
+use crate::query::common::query_processor::serialize_stream;
+use crate::serializer::common::{SerializeOptions, Serializer};
+use arrow_flight::sql::client::{
+  FlightSqlServiceClient, PreparedStatement as FlightPreparedStatement,
+};
+use napi::bindgen_prelude::*;
+use napi::Env;
+use std::collections::HashMap;
+use tonic::transport::Channel;
+
+/// Server side Flight SQL prepared statement, created with `InfluxDbClient.prepare`.
+///
+/// The statement is planned once and can be executed any number of times with different
+/// parameters until `close` is called.
+#[napi_derive::napi]
+pub struct PreparedStatement {
+  client: FlightSqlServiceClient<Channel>,
+  origin: String,
+  statement: Option<FlightPreparedStatement<Channel>>,
+  bound: bool,
+  serializer: Serializer,
+  serialize_options: SerializeOptions,
+}
+
+#[napi_derive::napi]
+impl PreparedStatement {
+  pub(crate) fn new(
+    client: FlightSqlServiceClient<Channel>,
+    origin: String,
+    statement: FlightPreparedStatement<Channel>,
+    serializer: Serializer,
+    serialize_options: SerializeOptions,
+  ) -> Self {
+    Self {
+      client,
+      origin,
+      statement: Some(statement),
+      bound: false,
+      serializer,
+      serialize_options,
+    }
+  }
+
+  /// Executes the statement. `params` replace those of earlier executions, without them the
+  /// statement runs without parameters.
+  #[napi_derive::napi(ts_return_type = "QueryStream")]
+  pub fn query(
+    &mut self,
+    #[napi(ts_arg_type = "Record<string, number | bigint | boolean | string | Date | null>")]
+    params: Option<HashMap<String, QueryParamValue>>,
+    env: &Env,
+  ) -> napi::Result<JsQueryStream<'_>> {
+    use napi::bindgen_prelude::block_on;
+
+    let statement = self
+      .statement
+      .as_mut()
+      .ok_or_else(|| napi::Error::from_reason("Prepared statement is closed"))?;
+    let (client, origin, bound) = (&self.client, &self.origin, &mut self.bound);
+
+    let (serializer, options) = (self.serializer.clone(), self.serialize_options.clone());
+    let stream = block_on(async {
+      let response = execute_prepared(client, origin, statement, params, bound).await?;
+      Ok::<_, napi::Error>(serialize_stream(response, serializer, options))
+    })?;
+
+    readable_stream(env, stream)
+  }
+
+  #[napi_derive::napi(getter)]
+  pub fn closed(&self) -> bool {
+    self.statement.is_none()
+  }
+
+  #[napi_derive::napi]
+  /// # Safety
+  ///
+  /// Releases the statement on the server, it can't be executed afterwards.
+  pub async unsafe fn close(&mut self) -> Result<()> {
+    if let Some(statement) = self.statement.take() {
+      statement
+        .close()
+        .await
+        .map_err(|e| napi::Error::from_reason(format!("Failed to close statement: {e}")))?;
+    }
+    Ok(())
+  }
+}
@@ -1,24 +1,25 @@
-use crate::client::channel::get_channel;
-use crate::client::http_client::get_http_client;
-pub use crate::client::options::{to_header_map, FlightOptions, QueryPayload, WriteOptions};
-use arrow_flight::{FlightClient, Ticket};
-use napi::bindgen_prelude::*;
-use napi::Status;
-use reqwest::Client;
-use tonic::codegen::Bytes;
-
-use crate::query::common::query_processor::into_stream;
-use crate::serializer::common::library_serializer::{LibraryReturnType, LibrarySerializer};
-use crate::serializer::common::raw_serializer::RawSerializer;
-use crate::serializer::common::unsafe_serializer::UnsafeSerializer;
-use crate::serializer::common::{Serializer, SerializerTrait};
-use crate::write::get_write_path;
+use crate::client::core::CoreClient;
+pub use crate::client::options::{
+  to_header_map, ExportFormat, ExportOptions, FlightOptions, HttpQueryFormat, QueryAllOptions,
+  QueryPayload, QueryTransport, QueryType, WriteOptions, DEFAULT_QUERY_ALL_MAX_ROWS,
+};
+use serde::de::DeserializeOwned;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::client::native::prepared_statement::PreparedStatement;
+use crate::error::Result;
+use crate::point::typed::InfluxPoint;
+use crate::query::common::metadata::TableDescription;
+use crate::query::common::query_processor::{
+  collect_batches, into_stream, into_typed_stream, serialize_rows, serialize_stream,
+};
+pub use crate::query::common::query_processor::{QueryRows, QueryStream};
+use crate::serializer::common::{SerializeOptions, Serializer, SerializerTrait};
 
 pub struct InfluxDBClient {
-  addr: String,
-  flight_client: FlightClient,
+  core: CoreClient,
   serializer: Serializer,
-  http_client: Client,
+  serialize_options: SerializeOptions,
 }
 
 impl InfluxDBClient {
@@ -28,112 +29,128 @@ impl InfluxDBClient {
     serializer: Option<Serializer>,
     options: Option<FlightOptions>,
   ) -> Self {
-    let channel = get_channel(addr.clone(), options).connect_lazy();
-
-    let http_client = get_http_client(token.clone().unwrap_or(String::from("")));
-
-    let mut flight_client = FlightClient::new(channel);
-
-    if let Some(token) = token {
-      flight_client
-        .add_header("authorization", format!("Bearer {token}").as_str())
-        .unwrap();
-    }
+    let serialize_options = SerializeOptions::new(options.as_ref());
 
     Self {
-      addr,
-      flight_client,
-      http_client,
+      core: CoreClient::new(addr, token, options),
       serializer: serializer.unwrap_or(Serializer::Unsafe),
+      serialize_options,
     }
   }
 
-  pub async fn query(
+  /// Runs the query with the serializer of the client.
+  pub async fn query(&mut self, query_payload: QueryPayload) -> Result<QueryStream> {
+    let options = self.serialize_options.for_query(&query_payload);
+    let response = self.core.record_batch_stream(query_payload).await?;
+    Ok(serialize_stream(response, self.serializer.clone(), options))
+  }
+
+  /// Runs the query with any [`SerializerTrait`] implementation, including ones defined outside
+  /// this crate, instead of the serializer the client was created with.
+  pub async fn query_with<S: SerializerTrait + 'static>(
     &mut self,
     query_payload: QueryPayload,
-  ) -> Result<
-    Either3<
-      napi::tokio_stream::wrappers::ReceiverStream<Result<LibraryReturnType>>,
-      napi::tokio_stream::wrappers::ReceiverStream<
-        Result<serde_json::Map<String, serde_json::Value>>,
-      >,
-      napi::tokio_stream::wrappers::ReceiverStream<Result<Buffer>>,
-    >,
-  > {
-    match self.serializer {
-      Serializer::Library => {
-        let stream = self.query_inner::<LibrarySerializer>(query_payload).await;
-        Ok(Either3::A(stream))
-      }
-      Serializer::Unsafe => {
-        let stream = self.query_inner::<UnsafeSerializer>(query_payload).await;
-        Ok(Either3::B(stream))
-      }
-      Serializer::Raw => {
-        let stream = self.query_inner::<RawSerializer>(query_payload).await;
-        Ok(Either3::C(stream))
-      }
-    }
+  ) -> Result<ReceiverStream<Result<<S as SerializerTrait>::Output>>> {
+    let options = self.serialize_options.for_query(&query_payload);
+    let response = self.core.record_batch_stream(query_payload).await?;
+    Ok(into_stream::<S, _>(response, options))
   }
 
-  pub async fn query_inner<S: SerializerTrait>(
+  /// Runs the query and deserializes every row into `T`, matching columns to fields by name.
+  pub async fn query_as<T>(
     &mut self,
     query_payload: QueryPayload,
-  ) -> napi::tokio_stream::wrappers::ReceiverStream<napi::Result<<S as SerializerTrait>::Output>>
+  ) -> Result<ReceiverStream<Result<T>>>
+  where
+    T: DeserializeOwned + Send + 'static,
   {
-    let payload: String = query_payload.into();
+    let response = self.core.record_batch_stream(query_payload).await?;
+    Ok(into_typed_stream(response))
+  }
+
+  /// Runs the query and collects every row, or a single Arrow IPC buffer for the `Raw`
+  /// serializer. Fails once more than `max_rows` rows are returned.
+  pub async fn query_all(
+    &mut self,
+    query_payload: QueryPayload,
+    options: Option<QueryAllOptions>,
+  ) -> Result<QueryRows> {
+    let max_rows = options
+      .and_then(|options| options.max_rows)
+      .unwrap_or(DEFAULT_QUERY_ALL_MAX_ROWS);
 
-    let ticket = Ticket {
-      ticket: Bytes::from(payload),
-    };
+    let serialize_options = self.serialize_options.for_query(&query_payload);
+    let response = self.core.record_batch_stream(query_payload).await?;
+    let batches = collect_batches(response, max_rows).await?;
 
-    let response = self.flight_client.do_get(ticket).await;
-    into_stream::<S>(response.unwrap())
+    serialize_rows(batches, self.serializer.clone(), &serialize_options).await
   }
 
-  async fn write_inner(
+  /// Runs the query and writes the result to `path` without handing rows to JS.
+  /// Returns the number of rows written.
+  pub async fn query_to_file(
+    &mut self,
+    query_payload: QueryPayload,
+    path: String,
+    format: ExportFormat,
+    options: Option<ExportOptions>,
+  ) -> Result<i64> {
+    self
+      .core
+      .query_to_file(query_payload, &path, format, options)
+      .await
+  }
+
+  /// Prepares `query` on `database` with Flight SQL, so it is planned once and can be executed
+  /// repeatedly with different parameters.
+  pub async fn prepare(&mut self, database: String, query: String) -> Result<PreparedStatement> {
+    let (client, statement) = self.core.prepare(&database, query).await?;
+
+    Ok(PreparedStatement::new(
+      client,
+      self.core.http().addr().to_string(),
+      statement,
+      self.serializer.clone(),
+      self.serialize_options.clone(),
+    ))
+  }
+
+  /// Lists the databases of an InfluxDB 3 Core or Enterprise server, other servers fail.
+  pub async fn list_databases(&self) -> Result<Vec<String>> {
+    self.core.list_databases().await
+  }
+
+  /// Lists the tables of `database`, without the system tables of the server.
+  pub async fn list_tables(&self, database: String) -> Result<Vec<String>> {
+    self.core.list_tables(&database).await
+  }
+
+  /// Returns the columns of `table` with their role (tag, field or time) and Arrow type.
+  pub async fn describe_table(&self, database: String, table: String) -> Result<TableDescription> {
+    self.core.describe_table(&database, &table).await
+  }
+
+  pub async fn write(
     &mut self,
     lines: Vec<String>,
     database: String,
     write_options: Option<WriteOptions>,
     org: Option<String>,
-  ) -> napi::Result<()> {
-    let (url, write_options) = get_write_path(&self.addr, database, org, write_options)?;
-
-    let headers = to_header_map(&write_options.headers.unwrap_or_default()).unwrap();
-    let response = &self
-      .http_client
-      .post(url)
-      .body(lines.join("\n"))
-      .headers(headers)
-      .send()
-      .await;
-
-    if let Err(e) = response {
-      match e.status() {
-        Some(reqwest::StatusCode::UNAUTHORIZED) => Err(napi::Error::from_reason("Unauthorized")),
-        _ => Err(napi::Error::from_status(Status::Cancelled)),
-      }
-    } else {
-      match response {
-        Ok(response) => match response.status() {
-          reqwest::StatusCode::OK => Ok(()),
-          reqwest::StatusCode::NO_CONTENT => Ok(()),
-          reqwest::StatusCode::UNAUTHORIZED => Err(napi::Error::from_reason("Unauthorized")),
-          _ => Err(napi::Error::from_reason("Unknown")),
-        },
-        Err(_error) => Err(napi::Error::from_status(Status::Cancelled)),
-      }
-    }
+  ) -> Result<()> {
+    self.core.write(lines, database, write_options, org).await
   }
 
-  pub async fn write(
+  /// Writes Rust values as points, e.g. structs deriving `InfluxPoint`.
+  pub async fn write_points<P: InfluxPoint>(
     &mut self,
-    lines: Vec<String>,
+    points: &[P],
     database: String,
     write_options: Option<WriteOptions>,
     org: Option<String>,
-  ) -> napi::Result<()> {
-    self.write_inner(lines, database, write_options, org).await
+  ) -> Result<()> {
+    self
+      .core
+      .write_points(points, database, write_options, org)
+      .await
   }
 }
@@ -1,2 +1,4 @@
 #[cfg(feature = "native")]
 pub mod client;
+#[cfg(feature = "native")]
+pub mod prepared_statement;
@@ -0,0 +1,106 @@
+use crate::client::options::QueryParamValue;
+use crate::error::{Error, Result};
+use crate::query::common::flight_sql::execute_prepared;
+use crate::query::common::query_processor::{
+  into_stream, into_typed_stream, serialize_stream, QueryStream,
+};
+use crate::serializer::common::{SerializeOptions, Serializer, SerializerTrait};
+use arrow::array::RecordBatch;
+use arrow_flight::sql::client::{
+  FlightSqlServiceClient, PreparedStatement as FlightPreparedStatement,
+};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Channel;
+
+/// Server side Flight SQL prepared statement, created with `InfluxDBClient::prepare`.
+pub struct PreparedStatement {
+  client: FlightSqlServiceClient<Channel>,
+  origin: String,
+  statement: FlightPreparedStatement<Channel>,
+  bound: bool,
+  serializer: Serializer,
+  serialize_options: SerializeOptions,
+}
+
+impl PreparedStatement {
+  pub(crate) fn new(
+    client: FlightSqlServiceClient<Channel>,
+    origin: String,
+    statement: FlightPreparedStatement<Channel>,
+    serializer: Serializer,
+    serialize_options: SerializeOptions,
+  ) -> Self {
+    Self {
+      client,
+      origin,
+      statement,
+      bound: false,
+      serializer,
+      serialize_options,
+    }
+  }
+
+  /// Executes the statement with the serializer of the client that prepared it.
+  ///
+  /// `params` replace those of earlier executions, `None` executes without parameters.
+  pub async fn query(
+    &mut self,
+    params: Option<HashMap<String, QueryParamValue>>,
+  ) -> Result<QueryStream> {
+    let response = self.execute(params).await?;
+    Ok(serialize_stream(
+      response,
+      self.serializer.clone(),
+      self.serialize_options.clone(),
+    ))
+  }
+
+  /// Executes the statement with any [`SerializerTrait`] implementation.
+  pub async fn query_with<S: SerializerTrait + 'static>(
+    &mut self,
+    params: Option<HashMap<String, QueryParamValue>>,
+  ) -> Result<ReceiverStream<Result<<S as SerializerTrait>::Output>>> {
+    let response = self.execute(params).await?;
+    Ok(into_stream::<S, _>(
+      response,
+      self.serialize_options.clone(),
+    ))
+  }
+
+  /// Executes the statement and deserializes every row into `T`.
+  pub async fn query_as<T>(
+    &mut self,
+    params: Option<HashMap<String, QueryParamValue>>,
+  ) -> Result<ReceiverStream<Result<T>>>
+  where
+    T: DeserializeOwned + Send + 'static,
+  {
+    let response = self.execute(params).await?;
+    Ok(into_typed_stream(response))
+  }
+
+  async fn execute(
+    &mut self,
+    params: Option<HashMap<String, QueryParamValue>>,
+  ) -> Result<ReceiverStream<Result<RecordBatch>>> {
+    execute_prepared(
+      &self.client,
+      &self.origin,
+      &mut self.statement,
+      params,
+      &mut self.bound,
+    )
+    .await
+  }
+
+  /// Releases the statement on the server.
+  pub async fn close(self) -> Result<()> {
+    self
+      .statement
+      .close()
+      .await
+      .map_err(|e| Error::Request(format!("Failed to close statement: {e}")))
+  }
+}
@@ -1,21 +1,30 @@
+use chrono::{DateTime, SecondsFormat};
+#[cfg(feature = "napi")]
+use napi::bindgen_prelude::{BigInt, FromNapiValue, Null, ToNapiValue};
+#[cfg(feature = "napi")]
+use napi::sys::{napi_env, napi_value};
+#[cfg(feature = "napi")]
+use napi::{check_status, type_of, Status, ValueType};
+#[cfg(feature = "napi")]
 use napi_derive::napi;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Display;
 
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 use serde_json::json;
 
 #[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-#[napi_derive::napi(string_enum)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "napi", napi(string_enum))]
 pub enum QueryType {
-  #[napi(value = "sql")]
+  #[cfg_attr(feature = "napi", napi(value = "sql"))]
   Sql,
-  #[napi(value = "influxql")]
+  #[cfg_attr(feature = "napi", napi(value = "influxql"))]
   InfluxQl,
-  #[napi(value = "flight_sql")]
+  /// Standard Flight SQL (`CommandStatementQuery`), usable with any Flight SQL server.
+  #[cfg_attr(feature = "napi", napi(value = "flight_sql"))]
   FlightSql,
 }
 
@@ -40,27 +49,27 @@ impl Display for QueryType {
   }
 }
 
-#[napi]
+#[cfg_attr(feature = "napi", napi)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Precision {
   V2(TimeUnitV2),
   V3(TimeUnitV3),
 }
 
-#[napi(string_enum)]
+#[cfg_attr(feature = "napi", napi(string_enum))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum TimeUnitV2 {
   /// Time in seconds.
-  #[napi(value = "s")]
+  #[cfg_attr(feature = "napi", napi(value = "s"))]
   Second,
   /// Time in milliseconds.
-  #[napi(value = "ms")]
+  #[cfg_attr(feature = "napi", napi(value = "ms"))]
   Millisecond,
   /// Time in microseconds.
-  #[napi(value = "us")]
+  #[cfg_attr(feature = "napi", napi(value = "us"))]
   Microsecond,
   /// Time in nanoseconds.
-  #[napi(value = "ns")]
+  #[cfg_attr(feature = "napi", napi(value = "ns"))]
   Nanosecond,
 }
 
@@ -75,20 +84,20 @@ impl fmt::Display for TimeUnitV2 {
   }
 }
 
-#[napi(string_enum)]
+#[cfg_attr(feature = "napi", napi(string_enum))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum TimeUnitV3 {
   /// Time in seconds.
-  #[napi(value = "second")]
+  #[cfg_attr(feature = "napi", napi(value = "second"))]
   Second,
   /// Time in milliseconds.
-  #[napi(value = "millisecond")]
+  #[cfg_attr(feature = "napi", napi(value = "millisecond"))]
   Millisecond,
   /// Time in microseconds.
-  #[napi(value = "microsecond")]
+  #[cfg_attr(feature = "napi", napi(value = "microsecond"))]
   Microsecond,
   /// Time in nanoseconds.
-  #[napi(value = "nanosecond")]
+  #[cfg_attr(feature = "napi", napi(value = "nanosecond"))]
   Nanosecond,
 }
 
@@ -153,11 +162,143 @@ pub fn to_header_map(
   Ok(headers)
 }
 
+/// Transport used to run queries.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryTransport {
+  /// Arrow Flight over gRPC (HTTP/2).
+  #[cfg_attr(feature = "napi", napi(value = "flight"))]
+  Flight,
+  /// Plain HTTP requests to `/api/v3/query_sql` and `/api/v3/query_influxql`.
+  #[cfg_attr(feature = "napi", napi(value = "http"))]
+  Http,
+}
+
+impl Default for QueryTransport {
+  fn default() -> Self {
+    Self::Flight
+  }
+}
+
+/// Response format requested from the HTTP query endpoints.
+///
+/// The text formats decode integer columns as floats and `time` as a nanosecond timestamp.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpQueryFormat {
+  /// Newline-delimited JSON, decoded incrementally.
+  #[cfg_attr(feature = "napi", napi(value = "jsonl"))]
+  Jsonl,
+  /// CSV with a header row, decoded incrementally.
+  #[cfg_attr(feature = "napi", napi(value = "csv"))]
+  Csv,
+  /// Parquet keeps the exact Arrow types, but the whole response is buffered before decoding.
+  #[cfg_attr(feature = "napi", napi(value = "parquet"))]
+  Parquet,
+}
+
+impl Default for HttpQueryFormat {
+  fn default() -> Self {
+    Self::Jsonl
+  }
+}
+
+impl HttpQueryFormat {
+  pub fn str(&self) -> &'static str {
+    match self {
+      Self::Jsonl => "jsonl",
+      Self::Csv => "csv",
+      Self::Parquet => "parquet",
+    }
+  }
+}
+
+/// Representation of timestamp values in rows returned by the `library` serializer.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampMode {
+  /// JS `Date`, truncated to milliseconds.
+  #[cfg_attr(feature = "napi", napi(value = "date"))]
+  Date,
+  /// `BigInt` nanoseconds since the Unix epoch, whatever the `Int64Mode`.
+  #[cfg_attr(feature = "napi", napi(value = "bigint"))]
+  BigInt,
+  /// Number of milliseconds since the Unix epoch.
+  #[cfg_attr(feature = "napi", napi(value = "number"))]
+  Number,
+  /// RFC3339 string in the timezone of the column, UTC when it has none.
+  #[cfg_attr(feature = "napi", napi(value = "string"))]
+  String,
+}
+
+/// Representation of durations, times of day and intervals in rows returned by the `library`
+/// serializer. Intervals are `{ months, days, nanoseconds }` objects in both modes.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemporalMode {
+  /// Durations as `{ value, unit }` objects, times of day as `HH:MM:SS.fffffffff` strings.
+  #[cfg_attr(feature = "napi", napi(value = "structured"))]
+  Structured,
+  /// Durations and times of day as `BigInt` nanoseconds, whatever the `Int64Mode`.
+  #[cfg_attr(feature = "napi", napi(value = "nanoseconds"))]
+  Nanoseconds,
+}
+
+/// Representation of 64-bit integers, `Date64` values and `number` timestamps in rows returned by
+/// the `library` serializer. `bigint` timestamps and `nanoseconds` durations stay `BigInt`, as
+/// their mode asks for one.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Int64Mode {
+  /// JS number, values above 2^53 lose precision.
+  #[cfg_attr(feature = "napi", napi(value = "number"))]
+  Number,
+  #[cfg_attr(feature = "napi", napi(value = "bigint"))]
+  BigInt,
+  /// Decimal string.
+  #[cfg_attr(feature = "napi", napi(value = "string"))]
+  String,
+  /// Number when the value is a safe integer, `BigInt` otherwise.
+  #[cfg_attr(feature = "napi", napi(value = "auto"))]
+  Auto,
+}
+
+impl Default for Int64Mode {
+  fn default() -> Self {
+    Self::Number
+  }
+}
+
+/// Representation of null cells in rows returned by the `library`, `unsafe` and `ndjson`
+/// serializers. Nulls nested in lists and structs are always `null`.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullMode {
+  /// The column is left out of the row.
+  #[cfg_attr(feature = "napi", napi(value = "omit"))]
+  Omit,
+  #[cfg_attr(feature = "napi", napi(value = "null"))]
+  Null,
+  /// The column is set to `undefined`, `ndjson` leaves it out like `omit`.
+  #[cfg_attr(feature = "napi", napi(value = "undefined"))]
+  Undefined,
+}
+
 #[cfg_attr(not(feature = "native"), napi_derive::napi(object))]
 #[derive(Clone)]
 pub struct FlightOptions {
   pub keep_alive_interval: Option<u32>,
   pub keep_alive_timeout: Option<u32>,
+  /** Transport used by `query` unless the payload overrides it. default flight */
+  pub transport: Option<QueryTransport>,
+  /** Timestamp representation used unless the payload overrides it */
+  pub timestamp_mode: Option<TimestampMode>,
+  /** 64-bit integer representation used unless the payload overrides it. default number */
+  pub int64_mode: Option<Int64Mode>,
+  /** Duration, time and interval representation used unless the payload overrides it */
+  pub temporal_mode: Option<TemporalMode>,
+  /** Null cell representation used unless the payload overrides it. default null, omit for ndjson */
+  pub null_mode: Option<NullMode>,
 }
 
 impl Default for FlightOptions {
@@ -165,16 +306,266 @@ impl Default for FlightOptions {
     Self {
       keep_alive_interval: Some(5),
       keep_alive_timeout: Some(20),
+      transport: None,
+      timestamp_mode: None,
+      int64_mode: None,
+      temporal_mode: None,
+      null_mode: None,
     }
   }
 }
 
+/// Value bound to a `$name` placeholder of a SQL or InfluxQL query.
+///
+/// From JS, integral numbers and `BigInt`s become `Int`, other numbers `Float`,
+/// `Date`s become `Timestamp` and `null`/`undefined` become `Null`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryParamValue {
+  Int(i64),
+  Float(f64),
+  Bool(bool),
+  String(String),
+  /// Nanoseconds since the Unix epoch, sent to the server as an RFC3339 string.
+  Timestamp(i64),
+  Null,
+}
+
+// Largest integer a JS number can hold without losing precision (2^53 - 1).
+#[cfg(feature = "napi")]
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+#[cfg(feature = "napi")]
+impl FromNapiValue for QueryParamValue {
+  unsafe fn from_napi_value(env: napi_env, napi_val: napi_value) -> napi::Result<Self> {
+    match type_of!(env, napi_val)? {
+      ValueType::Null | ValueType::Undefined => Ok(Self::Null),
+      ValueType::Boolean => Ok(Self::Bool(bool::from_napi_value(env, napi_val)?)),
+      ValueType::Number => {
+        let number = f64::from_napi_value(env, napi_val)?;
+        if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER {
+          Ok(Self::Int(number as i64))
+        } else {
+          Ok(Self::Float(number))
+        }
+      }
+      ValueType::BigInt => {
+        let (value, lossless) = BigInt::from_napi_value(env, napi_val)?.get_i64();
+        if !lossless {
+          return Err(napi::Error::new(
+            Status::InvalidArg,
+            "BigInt query parameter does not fit into a signed 64-bit integer",
+          ));
+        }
+        Ok(Self::Int(value))
+      }
+      ValueType::String => Ok(Self::String(String::from_napi_value(env, napi_val)?)),
+      ValueType::Object => {
+        let mut is_date = false;
+        check_status!(napi::sys::napi_is_date(env, napi_val, &mut is_date))?;
+        if !is_date {
+          return Err(napi::Error::new(
+            Status::InvalidArg,
+            "Object query parameters must be Date instances",
+          ));
+        }
+
+        let mut millis = 0f64;
+        check_status!(napi::sys::napi_get_date_value(env, napi_val, &mut millis))?;
+        Ok(Self::Timestamp((millis * 1_000_000.0) as i64))
+      }
+      value_type => Err(napi::Error::new(
+        Status::InvalidArg,
+        format!("Unsupported query parameter type: {value_type}"),
+      )),
+    }
+  }
+}
+
+#[cfg(feature = "napi")]
+impl ToNapiValue for QueryParamValue {
+  unsafe fn to_napi_value(env: napi_env, val: Self) -> napi::Result<napi_value> {
+    match val {
+      QueryParamValue::Int(i) => ToNapiValue::to_napi_value(env, i),
+      QueryParamValue::Float(f) => ToNapiValue::to_napi_value(env, f),
+      QueryParamValue::Bool(b) => ToNapiValue::to_napi_value(env, b),
+      QueryParamValue::String(s) => ToNapiValue::to_napi_value(env, s),
+      QueryParamValue::Timestamp(nanos) => {
+        let mut date = std::ptr::null_mut();
+        check_status!(napi::sys::napi_create_date(
+          env,
+          nanos as f64 / 1_000_000.0,
+          &mut date
+        ))?;
+        Ok(date)
+      }
+      QueryParamValue::Null => ToNapiValue::to_napi_value(env, Null),
+    }
+  }
+}
+
+impl Serialize for QueryParamValue {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      QueryParamValue::Int(i) => serializer.serialize_i64(*i),
+      QueryParamValue::Float(f) => serializer.serialize_f64(*f),
+      QueryParamValue::Bool(b) => serializer.serialize_bool(*b),
+      QueryParamValue::String(s) => serializer.serialize_str(s),
+      QueryParamValue::Timestamp(nanos) => serializer.serialize_str(
+        &DateTime::from_timestamp_nanos(*nanos).to_rfc3339_opts(SecondsFormat::AutoSi, true),
+      ),
+      QueryParamValue::Null => serializer.serialize_unit(),
+    }
+  }
+}
+
+impl From<i64> for QueryParamValue {
+  fn from(value: i64) -> Self {
+    Self::Int(value)
+  }
+}
+
+impl From<f64> for QueryParamValue {
+  fn from(value: f64) -> Self {
+    Self::Float(value)
+  }
+}
+
+impl From<bool> for QueryParamValue {
+  fn from(value: bool) -> Self {
+    Self::Bool(value)
+  }
+}
+
+impl From<String> for QueryParamValue {
+  fn from(value: String) -> Self {
+    Self::String(value)
+  }
+}
+
+impl From<&str> for QueryParamValue {
+  fn from(value: &str) -> Self {
+    Self::String(value.to_string())
+  }
+}
+
 #[cfg_attr(not(feature = "native"), napi_derive::napi(object))]
+#[derive(Default)]
 pub struct QueryPayload {
   pub database: String,
   pub query: String,
   pub _type: Option<QueryType>,
-  pub params: Option<HashMap<String, String>>,
+  #[cfg_attr(
+    not(feature = "native"),
+    napi(ts_type = "Record<string, number | bigint | boolean | string | Date | null>")
+  )]
+  pub params: Option<HashMap<String, QueryParamValue>>,
+  /** Overrides the client transport for this query */
+  pub transport: Option<QueryTransport>,
+  /** Response format used by the HTTP transport. default jsonl */
+  pub format: Option<HttpQueryFormat>,
+  /**
+   * Rows of a jsonl or csv HTTP response the schema is inferred from, columns that only appear
+   * later are left out. default 1024
+   */
+  pub schema_inference_rows: Option<u32>,
+  /** Overrides the client timestamp representation for this query */
+  pub timestamp_mode: Option<TimestampMode>,
+  /** Overrides the client 64-bit integer representation for this query */
+  pub int64_mode: Option<Int64Mode>,
+  /** Overrides the client duration, time and interval representation for this query */
+  pub temporal_mode: Option<TemporalMode>,
+  /** Overrides the client null cell representation for this query */
+  pub null_mode: Option<NullMode>,
+  /** Projection, renames and transforms applied to the result before it is serialized */
+  pub output: Option<OutputMapping>,
+}
+
+impl QueryPayload {
+  /// Payload of `query` against `database` with the client defaults.
+  pub fn new(database: impl Into<String>, query: impl Into<String>) -> Self {
+    Self {
+      database: database.into(),
+      query: query.into(),
+      ..Default::default()
+    }
+  }
+}
+
+/// Reshapes the record batches of a query before any serializer sees them.
+#[cfg_attr(not(feature = "native"), napi_derive::napi(object))]
+#[derive(Debug, Clone, Default)]
+pub struct OutputMapping {
+  /** Columns to keep, in output order. default all columns */
+  pub columns: Option<Vec<String>>,
+  /** New names by original column name, e.g. `{ time: "ts" }` */
+  pub rename: Option<HashMap<String, String>>,
+  /** Value transforms by original column name */
+  pub transforms: Option<HashMap<String, ColumnTransform>>,
+}
+
+#[cfg_attr(not(feature = "native"), napi_derive::napi(object))]
+#[derive(Debug, Clone, Default)]
+pub struct ColumnTransform {
+  /** Multiplies the values of a numeric column, which becomes a float64 column */
+  pub scale: Option<f64>,
+  /** Replaces nulls of a string or tag column with empty strings, other columns are rejected */
+  pub null_as_empty: Option<bool>,
+}
+
+#[cfg_attr(not(feature = "native"), napi_derive::napi(object))]
+#[derive(Clone, Default)]
+pub struct QueryAllOptions {
+  /** Maximum number of rows to collect, the query fails once it returns more. default 100000 */
+  pub max_rows: Option<u32>,
+}
+
+pub const DEFAULT_QUERY_ALL_MAX_ROWS: u32 = 100_000;
+
+/// File format written by `query_to_file`.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+  #[cfg_attr(feature = "napi", napi(value = "parquet"))]
+  Parquet,
+  /// CSV with a header row, timestamps as RFC3339.
+  #[cfg_attr(feature = "napi", napi(value = "csv"))]
+  Csv,
+  /// Newline-delimited JSON, one object per row.
+  #[cfg_attr(feature = "napi", napi(value = "ndjson"))]
+  Ndjson,
+}
+
+/// Compression codec of Parquet exports.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetCompression {
+  #[cfg_attr(feature = "napi", napi(value = "uncompressed"))]
+  Uncompressed,
+  #[cfg_attr(feature = "napi", napi(value = "snappy"))]
+  Snappy,
+  #[cfg_attr(feature = "napi", napi(value = "gzip"))]
+  Gzip,
+  #[cfg_attr(feature = "napi", napi(value = "lz4"))]
+  Lz4,
+  #[cfg_attr(feature = "napi", napi(value = "zstd"))]
+  Zstd,
+}
+
+impl Default for ParquetCompression {
+  fn default() -> Self {
+    Self::Zstd
+  }
+}
+
+#[cfg_attr(not(feature = "native"), napi_derive::napi(object))]
+#[derive(Clone, Default)]
+pub struct ExportOptions {
+  /** Parquet compression codec. default zstd */
+  pub compression: Option<ParquetCompression>,
+  /** Maximum number of rows per Parquet row group. default 1048576 */
+  pub max_row_group_size: Option<u32>,
+  /** Whether CSV files start with a header row. default true */
+  pub header: Option<bool>,
 }
 
 impl From<QueryPayload> for String {
@@ -196,3 +587,59 @@ impl From<QueryPayload> for String {
     json.to_string()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn ticket(params: HashMap<String, QueryParamValue>, query_type: QueryType) -> serde_json::Value {
+    let payload = QueryPayload {
+      database: "db".to_string(),
+      query: "SELECT * FROM cpu WHERE usage > $threshold".to_string(),
+      _type: Some(query_type),
+      params: Some(params),
+      ..Default::default()
+    };
+    let ticket: String = payload.into();
+    serde_json::from_str(&ticket).unwrap()
+  }
+
+  #[test]
+  fn test_params_keep_json_types() {
+    let params = HashMap::from([
+      ("int".to_string(), QueryParamValue::Int(42)),
+      ("float".to_string(), QueryParamValue::Float(0.5)),
+      ("bool".to_string(), QueryParamValue::Bool(true)),
+      ("string".to_string(), QueryParamValue::from("host-a")),
+      ("null".to_string(), QueryParamValue::Null),
+      (
+        "time".to_string(),
+        QueryParamValue::Timestamp(1_700_000_000_123_000_000),
+      ),
+    ]);
+
+    for query_type in [QueryType::Sql, QueryType::InfluxQl] {
+      let ticket = ticket(params.clone(), query_type);
+      let params = &ticket["params"];
+
+      assert_eq!(params["int"], json!(42));
+      assert_eq!(params["float"], json!(0.5));
+      assert_eq!(params["bool"], json!(true));
+      assert_eq!(params["string"], json!("host-a"));
+      assert_eq!(params["null"], serde_json::Value::Null);
+      assert_eq!(params["time"], json!("2023-11-14T22:13:20.123Z"));
+    }
+  }
+
+  #[test]
+  fn test_query_type_in_ticket() {
+    assert_eq!(
+      ticket(HashMap::new(), QueryType::Sql)["query_type"],
+      json!("sql")
+    );
+    assert_eq!(
+      ticket(HashMap::new(), QueryType::InfluxQl)["query_type"],
+      json!("influxql")
+    );
+  }
+}
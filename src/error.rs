@@ -0,0 +1,149 @@
+use std::fmt;
+
+/// Error of the Rust client API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+  /// The token was rejected by the server.
+  Unauthorized,
+  /// The server answered with an error status.
+  Server { status: u16, message: String },
+  /// A Flight call failed with a gRPC status other than `Unauthenticated`.
+  #[cfg(not(target_arch = "wasm32"))]
+  Flight { code: tonic::Code, message: String },
+  /// The server could not be reached.
+  Transport(String),
+  /// A value was rejected before anything was sent, e.g. a field of the wrong type.
+  InvalidInput(String),
+  /// Any other failure of a request, a query or the decoding of its result.
+  Request(String),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+  /// Error for an unsuccessful HTTP response with its body as message.
+  pub(crate) fn from_response(status: reqwest::StatusCode, message: String) -> Self {
+    match status {
+      reqwest::StatusCode::UNAUTHORIZED => Self::Unauthorized,
+      status => Self::Server {
+        status: status.as_u16(),
+        message,
+      },
+    }
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  fn from_status(status: &tonic::Status) -> Self {
+    match status.code() {
+      tonic::Code::Unauthenticated => Self::Unauthorized,
+      tonic::Code::Unavailable => Self::Transport(status.message().to_string()),
+      code => Self::Flight {
+        code,
+        message: status.message().to_string(),
+      },
+    }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Unauthorized => write!(f, "Unauthorized"),
+      Self::Server { status, message } if message.is_empty() => {
+        write!(f, "Server responded with status {status}")
+      }
+      Self::Server { status, message } => {
+        write!(f, "Server responded with status {status}: {message}")
+      }
+      #[cfg(not(target_arch = "wasm32"))]
+      Self::Flight { code, message } => write!(f, "Flight request failed ({code:?}): {message}"),
+      Self::Transport(message) | Self::InvalidInput(message) | Self::Request(message) => {
+        write!(f, "{message}")
+      }
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+  fn from(error: reqwest::Error) -> Self {
+    match error.status() {
+      Some(status) => Self::from_response(status, error.to_string()),
+      None => Self::Transport(format!("HTTP request failed: {error}")),
+    }
+  }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<tonic::Status> for Error {
+  fn from(status: tonic::Status) -> Self {
+    Self::from_status(&status)
+  }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<arrow_flight::error::FlightError> for Error {
+  fn from(error: arrow_flight::error::FlightError) -> Self {
+    use arrow_flight::error::FlightError;
+
+    match error {
+      FlightError::Tonic(status) => Self::from_status(&status),
+      FlightError::Arrow(error) => error.into(),
+      FlightError::ExternalError(error) => match error.downcast::<Self>() {
+        Ok(error) => *error,
+        Err(error) => Self::Request(error.to_string()),
+      },
+      error => Self::Request(error.to_string()),
+    }
+  }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<arrow::error::ArrowError> for Error {
+  fn from(error: arrow::error::ArrowError) -> Self {
+    use arrow::error::ArrowError;
+
+    match error {
+      ArrowError::ExternalError(error) => match error.downcast::<Self>() {
+        Ok(error) => *error,
+        Err(error) => Self::Request(error.to_string()),
+      },
+      error => Self::Request(error.to_string()),
+    }
+  }
+}
+
+#[cfg(feature = "napi")]
+impl From<Error> for napi::Error {
+  fn from(error: Error) -> Self {
+    napi::Error::from_reason(error.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_status_conversion() {
+    let unauthorized = Error::from(tonic::Status::unauthenticated("invalid token"));
+    let not_found = Error::from(arrow_flight::error::FlightError::Tonic(
+      tonic::Status::not_found("table").into(),
+    ));
+
+    assert_eq!(unauthorized, Error::Unauthorized);
+    assert_eq!(
+      not_found,
+      Error::Flight {
+        code: tonic::Code::NotFound,
+        message: "table".to_string(),
+      }
+    );
+    assert_eq!(
+      Error::from_response(reqwest::StatusCode::BAD_REQUEST, "invalid line".to_string())
+        .to_string(),
+      "Server responded with status 400: invalid line"
+    );
+  }
+}
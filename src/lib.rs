@@ -2,12 +2,26 @@
 // #[macro_use]
 // extern crate napi_derive;
 
+use crate::serializer::shared_strings::SharedStrings;
+#[cfg(feature = "napi")]
 use napi::bindgen_prelude::*;
+#[cfg(feature = "napi")]
+use napi::check_status;
+#[cfg(feature = "napi")]
 use napi::sys::{napi_env, napi_value};
+#[cfg(feature = "napi")]
 use napi_derive::napi;
 use std::collections::HashMap;
+use std::sync::Arc;
+
+#[cfg(not(any(feature = "napi", feature = "native")))]
+compile_error!("enable the `napi` feature for the JS bindings or `native` for the Rust client");
+
+// Lets `#[derive(InfluxPoint)]` name the crate from within it
+extern crate self as influxdb3_napi;
 
 pub mod client;
+pub mod error;
 pub mod point;
 pub mod query;
 pub mod serializer;
@@ -46,13 +60,46 @@ pub enum Value {
   Date64(i64),
   FixedList(Vec<Value>),
   List(Vec<Value>),
+  /// Milliseconds since the Unix epoch, returned as a JS `Date`.
+  Date(f64),
+  /// Returned as a JS `BigInt`.
+  BigInt(i128),
+  /// Dictionary value shared by every row of a batch that references it.
+  Dictionary(DictionaryValue),
+  /// Binary columns, returned as a `Buffer`.
+  Binary(Vec<u8>),
+  /// Struct and map values, returned as a plain object.
+  Object(Vec<(String, Value)>),
   Null,
   Fallback,
 }
 
+/// Value of a dictionary column, an index into the dictionary strings of its batch.
+#[derive(Debug, Clone)]
+pub struct DictionaryValue {
+  values: Arc<SharedStrings>,
+  key: usize,
+}
+
+impl DictionaryValue {
+  pub(crate) fn new(values: Arc<SharedStrings>, key: usize) -> Self {
+    Self { values, key }
+  }
+
+  pub fn as_str(&self) -> &str {
+    self.values.get(self.key).unwrap_or_default()
+  }
+
+  /// Dictionary strings of the batch.
+  pub fn values(&self) -> &Arc<SharedStrings> {
+    &self.values
+  }
+}
+
+#[cfg(feature = "napi")]
 static FALLBACK_STR: &str = "<unsupported type>";
 
-#[cfg_attr(not(feature = "native"), napi)]
+#[cfg(feature = "napi")]
 impl ToNapiValue for Value {
   unsafe fn to_napi_value(env: napi_env, val: Self) -> napi::Result<napi_value> {
     match val {
@@ -76,6 +123,24 @@ impl ToNapiValue for Value {
       Value::String(s) => ToNapiValue::to_napi_value(env, s),
       Value::FixedList(fl) => ToNapiValue::to_napi_value(env, Ok(fl)),
       Value::List(l) => ToNapiValue::to_napi_value(env, Ok(l)),
+      Value::Date(millis) => {
+        let mut date = std::ptr::null_mut();
+        check_status!(napi::sys::napi_create_date(env, millis, &mut date))?;
+        Ok(date)
+      }
+      Value::BigInt(i) => ToNapiValue::to_napi_value(env, i),
+      Value::Dictionary(value) => value.values.to_napi_value(env, value.key),
+      Value::Binary(b) => ToNapiValue::to_napi_value(env, Buffer::from(b)),
+      Value::Object(entries) => {
+        let mut object = std::ptr::null_mut();
+        check_status!(napi::sys::napi_create_object(env, &mut object))?;
+        for (key, value) in entries {
+          let key = ToNapiValue::to_napi_value(env, key)?;
+          let value = ToNapiValue::to_napi_value(env, value)?;
+          check_status!(napi::sys::napi_set_property(env, object, key, value))?;
+        }
+        Ok(object)
+      }
       Value::Fallback => ToNapiValue::to_napi_value(env, Ok(FALLBACK_STR)),
     }
   }
@@ -1,9 +1,13 @@
 mod escape;
 pub mod point_values;
+pub mod typed;
 use crate::client::options::TimeUnitV2;
+use crate::error::Result;
 use crate::point::escape::{escape, COMMA_EQ_SPACE, COMMA_SPACE};
-use crate::point::point_values::{PointFieldType, PointValues};
+use crate::point::point_values::{PointFieldType, PointFieldValue, PointValues};
+#[cfg(not(feature = "native"))]
 use napi::bindgen_prelude::Either5;
+#[cfg(not(feature = "native"))]
 use napi_derive::napi;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
@@ -49,12 +53,12 @@ impl Point {
   }
 
   #[cfg_attr(not(feature = "native"), napi(getter))]
-  pub fn timestamp(&self) -> Option<u32> {
+  pub fn timestamp(&self) -> Option<i64> {
     self.values.timestamp()
   }
 
   #[cfg_attr(not(feature = "native"), napi(setter))]
-  pub fn set_timestamp(&mut self, timestamp: u32) {
+  pub fn set_timestamp(&mut self, timestamp: i64) {
     self.values.set_timestamp(timestamp);
   }
 
@@ -78,8 +82,7 @@ impl Point {
     self.values.tag_names()
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_float_field(&self, name: String) -> napi::Result<Option<f64>> {
+  pub fn get_float_field(&self, name: String) -> Result<Option<f64>> {
     self.values.get_float_field(name)
   }
 
@@ -88,8 +91,7 @@ impl Point {
     self.values.set_float_field(name, value)
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_int_field(&self, name: String) -> napi::Result<Option<i64>> {
+  pub fn get_int_field(&self, name: String) -> Result<Option<i64>> {
     self.values.get_int_field(name)
   }
 
@@ -98,18 +100,15 @@ impl Point {
     self.values.set_int_field(name, value)
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_uinteger_field(&self, name: String) -> napi::Result<Option<u32>> {
+  pub fn get_uinteger_field(&self, name: String) -> Result<Option<u64>> {
     self.values.get_uinteger_field(name)
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn set_uinteger_field(&mut self, name: String, value: u32) {
+  pub fn set_uinteger_field(&mut self, name: String, value: u64) {
     self.values.set_uinteger_field(name, value)
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_string_field(&self, name: String) -> napi::Result<Option<String>> {
+  pub fn get_string_field(&self, name: String) -> Result<Option<String>> {
     self.values.get_string_field(name)
   }
 
@@ -118,8 +117,7 @@ impl Point {
     self.values.set_string_field(name, value)
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_boolean_field(&self, name: String) -> napi::Result<Option<bool>> {
+  pub fn get_boolean_field(&self, name: String) -> Result<Option<bool>> {
     self.values.get_boolean_field(name)
   }
 
@@ -133,16 +131,11 @@ impl Point {
     self.values.get_field_type(name)
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn set_fields(
-    &mut self,
-    values: HashMap<String, Either5<bool, f64, u32, i64, String>>,
-  ) -> napi::Result<()> {
+  pub fn set_fields(&mut self, values: HashMap<String, PointFieldValue>) -> Result<()> {
     self.values.set_fields(values)
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn remove_field(&mut self, name: String) -> napi::Result<()> {
+  pub fn remove_field(&mut self, name: String) -> Result<()> {
     self.values.remove_field(name)
   }
 
@@ -254,7 +247,7 @@ impl Point {
   }
 }
 
-#[napi]
+#[cfg_attr(not(feature = "native"), napi)]
 impl Display for Point {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(
@@ -265,6 +258,58 @@ impl Display for Point {
   }
 }
 
+/// JS bindings of the fallible methods, which throw instead of returning the crate `Error`.
+#[cfg(not(feature = "native"))]
+#[napi]
+impl Point {
+  #[napi(js_name = "getFloatField")]
+  pub fn js_get_float_field(&self, name: String) -> napi::Result<Option<f64>> {
+    Ok(self.get_float_field(name)?)
+  }
+
+  #[napi(js_name = "getIntField")]
+  pub fn js_get_int_field(&self, name: String) -> napi::Result<Option<i64>> {
+    Ok(self.get_int_field(name)?)
+  }
+
+  #[napi(js_name = "getUintegerField")]
+  pub fn js_get_uinteger_field(&self, name: String) -> napi::Result<Option<f64>> {
+    Ok(self.get_uinteger_field(name)?.map(|value| value as f64))
+  }
+
+  #[napi(js_name = "setUintegerField")]
+  pub fn js_set_uinteger_field(&mut self, name: String, value: u32) {
+    self.set_uinteger_field(name, value.into())
+  }
+
+  #[napi(js_name = "getStringField")]
+  pub fn js_get_string_field(&self, name: String) -> napi::Result<Option<String>> {
+    Ok(self.get_string_field(name)?)
+  }
+
+  #[napi(js_name = "getBooleanField")]
+  pub fn js_get_boolean_field(&self, name: String) -> napi::Result<Option<bool>> {
+    Ok(self.get_boolean_field(name)?)
+  }
+
+  #[napi(js_name = "setFields")]
+  pub fn js_set_fields(
+    &mut self,
+    values: HashMap<String, Either5<bool, f64, u32, i64, String>>,
+  ) -> napi::Result<()> {
+    let values = values
+      .into_iter()
+      .map(|(name, value)| (name, value.into()))
+      .collect();
+    Ok(self.set_fields(values)?)
+  }
+
+  #[napi(js_name = "removeField")]
+  pub fn js_remove_field(&mut self, name: String) -> napi::Result<()> {
+    Ok(self.remove_field(name)?)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -336,7 +381,7 @@ mod tests {
 
     line_four.set_boolean_field("mybool".to_string(), false);
     line_four.set_int_field("mysigned".to_string(), 51_i64);
-    line_four.set_uinteger_field("myunsigned".to_string(), 51_u32);
+    line_four.set_uinteger_field("myunsigned".to_string(), 51_u64);
     line_four.set_float_field("myfloat".to_string(), 51.0);
     line_four.set_string_field("mystring".to_string(), "some value".to_string());
     line_four.set_string_field(
@@ -1,12 +1,15 @@
+#[cfg(feature = "napi")]
 use napi::bindgen_prelude::Either5;
 
+use crate::error::{Error, Result};
 use crate::point::escape::{escape, DOUBLE_QUOTE};
+#[cfg(feature = "napi")]
 use napi_derive::napi;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
-#[napi(string_enum = "lowercase")]
+#[cfg_attr(feature = "napi", napi(string_enum = "lowercase"))]
 #[derive(Debug, Clone, PartialEq)]
 pub enum PointFieldType {
   Float,
@@ -16,17 +19,27 @@ pub enum PointFieldType {
   Boolean,
 }
 
-#[napi]
-#[derive(Clone)]
+#[cfg_attr(feature = "napi", napi)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PointFieldValue {
   Float(f64),
   Integer(i64),
-  UInteger(u32),
+  UInteger(u64),
   String(String),
   Boolean(bool),
 }
 
-// type Field<'a> = Either5<&'a bool, &'a f64, &'a u32, &'a i64, &'a String>;
+impl PointFieldValue {
+  pub fn field_type(&self) -> PointFieldType {
+    match self {
+      PointFieldValue::Float(_) => PointFieldType::Float,
+      PointFieldValue::Integer(_) => PointFieldType::Integer,
+      PointFieldValue::UInteger(_) => PointFieldType::UInteger,
+      PointFieldValue::String(_) => PointFieldType::String,
+      PointFieldValue::Boolean(_) => PointFieldType::Boolean,
+    }
+  }
+}
 
 impl Display for PointFieldValue {
   fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -40,11 +53,24 @@ impl Display for PointFieldValue {
   }
 }
 
+#[cfg(feature = "napi")]
+impl From<Either5<bool, f64, u32, i64, String>> for PointFieldValue {
+  fn from(value: Either5<bool, f64, u32, i64, String>) -> Self {
+    match value {
+      Either5::A(value) => PointFieldValue::Boolean(value),
+      Either5::B(value) => PointFieldValue::Float(value),
+      Either5::C(value) => PointFieldValue::UInteger(value.into()),
+      Either5::D(value) => PointFieldValue::Integer(value),
+      Either5::E(value) => PointFieldValue::String(value),
+    }
+  }
+}
+
 #[derive(Clone)]
 #[cfg_attr(not(feature = "native"), napi)]
 pub struct PointValues {
   pub(crate) name: Option<String>,
-  time: Option<u32>,
+  time: Option<i64>,
   tags: BTreeMap<String, String>,
   fields: BTreeMap<String, PointFieldValue>, //BTreeMap
 }
@@ -57,12 +83,12 @@ impl PointValues {
   }
 
   #[cfg_attr(not(feature = "native"), napi(getter))]
-  pub fn timestamp(&self) -> Option<u32> {
+  pub fn timestamp(&self) -> Option<i64> {
     self.time
   }
 
   #[cfg_attr(not(feature = "native"), napi_derive::napi)]
-  pub fn set_timestamp(&mut self, time: u32) {
+  pub fn set_timestamp(&mut self, time: i64) {
     self.time = Some(time);
   }
 
@@ -90,7 +116,7 @@ impl PointValues {
     }
   }
 
-  pub fn get_timestamp(&self) -> Option<u32> {
+  pub fn get_timestamp(&self) -> Option<i64> {
     self.time
   }
 
@@ -114,11 +140,12 @@ impl PointValues {
     self.tags.keys().cloned().collect()
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_float_field(&self, name: String) -> napi::Result<Option<f64>> {
-    match self.get_field(name, Some(PointFieldType::Float))? {
-      Some(Either5::B(value)) => Ok(Some(*value)),
-      Some(_) => Err(napi::Error::from_reason("Field exists but is not a float")),
+  pub fn get_float_field(&self, name: String) -> Result<Option<f64>> {
+    match self.fields.get(&name) {
+      Some(PointFieldValue::Float(value)) => Ok(Some(*value)),
+      Some(_) => Err(Error::InvalidInput(format!(
+        "Field '{name}' exists but is not a float"
+      ))),
       None => Ok(None),
     }
   }
@@ -128,13 +155,12 @@ impl PointValues {
     self.fields.insert(name, PointFieldValue::Float(value));
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_int_field(&self, name: String) -> napi::Result<Option<i64>> {
-    match self.get_field(name, Some(PointFieldType::Integer))? {
-      Some(Either5::D(value)) => Ok(Some(*value)),
-      Some(_) => Err(napi::Error::from_reason(
-        "Field exists but is not an integer",
-      )),
+  pub fn get_int_field(&self, name: String) -> Result<Option<i64>> {
+    match self.fields.get(&name) {
+      Some(PointFieldValue::Integer(value)) => Ok(Some(*value)),
+      Some(_) => Err(Error::InvalidInput(format!(
+        "Field '{name}' exists but is not an integer"
+      ))),
       None => Ok(None),
     }
   }
@@ -144,27 +170,26 @@ impl PointValues {
     self.fields.insert(name, PointFieldValue::Integer(value));
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_uinteger_field(&self, name: String) -> napi::Result<Option<u32>> {
-    match self.get_field(name, Some(PointFieldType::UInteger))? {
-      Some(Either5::C(value)) => Ok(Some(*value)),
-      Some(_) => Err(napi::Error::from_reason(
-        "Field exists but is not an unsigned integer",
-      )),
+  pub fn get_uinteger_field(&self, name: String) -> Result<Option<u64>> {
+    match self.fields.get(&name) {
+      Some(PointFieldValue::UInteger(value)) => Ok(Some(*value)),
+      Some(_) => Err(Error::InvalidInput(format!(
+        "Field '{name}' exists but is not an unsigned integer"
+      ))),
       None => Ok(None),
     }
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn set_uinteger_field(&mut self, name: String, value: u32) {
+  pub fn set_uinteger_field(&mut self, name: String, value: u64) {
     self.fields.insert(name, PointFieldValue::UInteger(value));
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_string_field(&self, name: String) -> napi::Result<Option<String>> {
-    match self.get_field(name, Some(PointFieldType::String))? {
-      Some(Either5::E(value)) => Ok(Some(value.clone())),
-      Some(_) => Err(napi::Error::from_reason("Field exists but is not a string")),
+  pub fn get_string_field(&self, name: String) -> Result<Option<String>> {
+    match self.fields.get(&name) {
+      Some(PointFieldValue::String(value)) => Ok(Some(value.clone())),
+      Some(_) => Err(Error::InvalidInput(format!(
+        "Field '{name}' exists but is not a string"
+      ))),
       None => Ok(None),
     }
   }
@@ -174,13 +199,12 @@ impl PointValues {
     self.fields.insert(name, PointFieldValue::String(value));
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_boolean_field(&self, name: String) -> napi::Result<Option<bool>> {
-    match self.get_field(name, Some(PointFieldType::Boolean))? {
-      Some(Either5::A(value)) => Ok(Some(*value)),
-      Some(_) => Err(napi::Error::from_reason(
-        "Field exists but is not a boolean",
-      )),
+  pub fn get_boolean_field(&self, name: String) -> Result<Option<bool>> {
+    match self.fields.get(&name) {
+      Some(PointFieldValue::Boolean(value)) => Ok(Some(*value)),
+      Some(_) => Err(Error::InvalidInput(format!(
+        "Field '{name}' exists but is not a boolean"
+      ))),
       None => Ok(None),
     }
   }
@@ -192,117 +216,44 @@ impl PointValues {
 
   #[cfg_attr(not(feature = "native"), napi)]
   pub fn get_field_type(&self, name: String) -> Option<PointFieldType> {
-    if let Some(field) = self.fields.get(&name) {
-      match field {
-        PointFieldValue::Float(_) => Some(PointFieldType::Float),
-        PointFieldValue::Integer(_) => Some(PointFieldType::Integer),
-        PointFieldValue::UInteger(_) => Some(PointFieldType::UInteger),
-        PointFieldValue::String(_) => Some(PointFieldType::String),
-        PointFieldValue::Boolean(_) => Some(PointFieldType::Boolean),
-      }
-    } else {
-      None
-    }
+    self.fields.get(&name).map(PointFieldValue::field_type)
   }
 
-  #[allow(clippy::type_complexity)]
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn get_field(
-    &self,
-    name: String,
-    expected_type: Option<PointFieldType>,
-  ) -> napi::Result<Option<Either5<&bool, &f64, &u32, &i64, &String>>> {
-    let field_entry = self.fields.get(&name);
-
-    match field_entry {
-      Some(field_entry) => match field_entry {
-        PointFieldValue::Boolean(b) => Ok(Some(Either5::A(b))),
-        PointFieldValue::Float(f) => Ok(Some(Either5::B(f))),
-        PointFieldValue::UInteger(u) => Ok(Some(Either5::C(u))),
-        PointFieldValue::Integer(i) => Ok(Some(Either5::D(i))),
-        PointFieldValue::String(i) => Ok(Some(Either5::E(i))),
-      },
-      None => Ok(None),
-    }
+  pub fn get_field(&self, name: &str) -> Option<&PointFieldValue> {
+    self.fields.get(name)
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
+  /// Sets a field, failing when `field_type` is given and differs from the type of `value`.
   pub fn set_field(
     &mut self,
     name: String,
-    value: Either5<bool, f64, u32, i64, String>,
+    value: PointFieldValue,
     field_type: Option<PointFieldType>,
-  ) -> napi::Result<()> {
-    let field_entry = match value {
-      Either5::A(bool_value) => {
-        let field_type = field_type.unwrap_or(PointFieldType::Boolean);
-        if field_type != PointFieldType::Boolean {
-          return Err(napi::Error::from_reason(format!(
-            "Type mismatch: provided boolean value but expected type {field_type:?}"
-          )));
-        }
-        PointFieldValue::Boolean(bool_value)
-      }
-      Either5::B(float_value) => {
-        let field_type = field_type.unwrap_or(PointFieldType::Float);
-        if field_type != PointFieldType::Float {
-          return Err(napi::Error::from_reason(format!(
-            "Type mismatch: provided float value but expected type {field_type:?}"
-          )));
-        }
-        PointFieldValue::Float(float_value)
-      }
-      Either5::C(uint_value) => {
-        let field_type = field_type.unwrap_or(PointFieldType::UInteger);
-        if field_type != PointFieldType::UInteger {
-          return Err(napi::Error::from_reason(format!(
-            "Type mismatch: provided unsigned integer value but expected type {field_type:?}"
-          )));
-        }
-        PointFieldValue::UInteger(uint_value)
+  ) -> Result<()> {
+    if let Some(field_type) = field_type {
+      if field_type != value.field_type() {
+        return Err(Error::InvalidInput(format!(
+          "Type mismatch: provided {:?} value but expected type {field_type:?}",
+          value.field_type()
+        )));
       }
-      Either5::D(int_value) => {
-        let field_type = field_type.unwrap_or(PointFieldType::Integer);
-        if field_type != PointFieldType::Integer {
-          return Err(napi::Error::from_reason(format!(
-            "Type mismatch: provided integer value but expected type {field_type:?}"
-          )));
-        }
-        PointFieldValue::Integer(int_value)
-      }
-      Either5::E(string_value) => {
-        let field_type = field_type.unwrap_or(PointFieldType::String);
-        if field_type != PointFieldType::String {
-          return Err(napi::Error::from_reason(format!(
-            "Type mismatch: provided string value but expected type {field_type:?}"
-          )));
-        }
-        PointFieldValue::String(string_value)
-      }
-    };
+    }
 
-    self.fields.insert(name, field_entry);
+    self.fields.insert(name, value);
     Ok(())
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn set_fields(
-    &mut self,
-    values: HashMap<String, Either5<bool, f64, u32, i64, String>>,
-  ) -> napi::Result<()> {
+  pub fn set_fields(&mut self, values: HashMap<String, PointFieldValue>) -> Result<()> {
     for (name, value) in values {
       self.set_field(name, value, None)?
     }
     Ok(())
   }
 
-  #[cfg_attr(not(feature = "native"), napi)]
-  pub fn remove_field(&mut self, name: String) -> napi::Result<()> {
+  pub fn remove_field(&mut self, name: String) -> Result<()> {
     match self.fields.remove(&name) {
       Some(_) => Ok(()),
-      None => Err(napi::Error::from_reason(format!(
-        "Field '{name}' not found"
-      ))),
+      None => Err(Error::InvalidInput(format!("Field '{name}' not found"))),
     }
   }
 
@@ -316,3 +267,81 @@ impl PointValues {
     !self.fields.is_empty()
   }
 }
+
+/// JS bindings of the fallible methods, which throw instead of returning the crate `Error`.
+#[cfg(not(feature = "native"))]
+#[napi]
+impl PointValues {
+  #[napi(js_name = "getFloatField")]
+  pub fn js_get_float_field(&self, name: String) -> napi::Result<Option<f64>> {
+    Ok(self.get_float_field(name)?)
+  }
+
+  #[napi(js_name = "getIntField")]
+  pub fn js_get_int_field(&self, name: String) -> napi::Result<Option<i64>> {
+    Ok(self.get_int_field(name)?)
+  }
+
+  #[napi(js_name = "getUintegerField")]
+  pub fn js_get_uinteger_field(&self, name: String) -> napi::Result<Option<f64>> {
+    Ok(self.get_uinteger_field(name)?.map(|value| value as f64))
+  }
+
+  #[napi(js_name = "setUintegerField")]
+  pub fn js_set_uinteger_field(&mut self, name: String, value: u32) {
+    self.set_uinteger_field(name, value.into())
+  }
+
+  #[napi(js_name = "getStringField")]
+  pub fn js_get_string_field(&self, name: String) -> napi::Result<Option<String>> {
+    Ok(self.get_string_field(name)?)
+  }
+
+  #[napi(js_name = "getBooleanField")]
+  pub fn js_get_boolean_field(&self, name: String) -> napi::Result<Option<bool>> {
+    Ok(self.get_boolean_field(name)?)
+  }
+
+  #[allow(clippy::type_complexity)]
+  #[napi(js_name = "getField")]
+  pub fn js_get_field(
+    &self,
+    name: String,
+    _expected_type: Option<PointFieldType>,
+  ) -> napi::Result<Option<Either5<&bool, &f64, f64, &i64, &String>>> {
+    Ok(self.get_field(&name).map(|field| match field {
+      PointFieldValue::Boolean(b) => Either5::A(b),
+      PointFieldValue::Float(f) => Either5::B(f),
+      PointFieldValue::UInteger(u) => Either5::C(*u as f64),
+      PointFieldValue::Integer(i) => Either5::D(i),
+      PointFieldValue::String(s) => Either5::E(s),
+    }))
+  }
+
+  #[napi(js_name = "setField")]
+  pub fn js_set_field(
+    &mut self,
+    name: String,
+    value: Either5<bool, f64, u32, i64, String>,
+    field_type: Option<PointFieldType>,
+  ) -> napi::Result<()> {
+    Ok(self.set_field(name, value.into(), field_type)?)
+  }
+
+  #[napi(js_name = "setFields")]
+  pub fn js_set_fields(
+    &mut self,
+    values: HashMap<String, Either5<bool, f64, u32, i64, String>>,
+  ) -> napi::Result<()> {
+    let values = values
+      .into_iter()
+      .map(|(name, value)| (name, value.into()))
+      .collect();
+    Ok(self.set_fields(values)?)
+  }
+
+  #[napi(js_name = "removeField")]
+  pub fn js_remove_field(&mut self, name: String) -> napi::Result<()> {
+    Ok(self.remove_field(name)?)
+  }
+}
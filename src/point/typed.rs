@@ -0,0 +1,336 @@
+use crate::client::options::{Precision, TimeUnitV2, TimeUnitV3, WriteOptions};
+use crate::error::{Error, Result};
+use crate::point::Point;
+use chrono::{DateTime, TimeZone};
+use serde::Serialize;
+
+/// Derives [`InfluxPoint`](trait@InfluxPoint) for a struct with named members.
+///
+/// ```ignore
+/// #[derive(Clone, Serialize, InfluxPoint)]
+/// #[influx(measurement = "cpu")]
+/// pub struct Cpu<'a> {
+///   /// Host the sample was taken on.
+///   #[influx(tag)]
+///   pub host: &'a str,
+///   pub usage: f64,
+///   #[influx(rename = "cores")]
+///   #[serde(rename = "cores")]
+///   pub core_count: Option<i64>,
+///   /// Converted to the precision of the write.
+///   #[influx(timestamp)]
+///   pub time: DateTime<Utc>,
+/// }
+/// ```
+pub use influxdb3_napi_derive::InfluxPoint;
+
+/// Rust value written as a single point, usually derived with
+/// [`#[derive(InfluxPoint)]`](derive@InfluxPoint).
+pub trait InfluxPoint {
+  /// Point with its timestamp in `precision`, the precision of the write.
+  fn to_point_with_precision(&self, precision: TimeUnitV2) -> Point;
+
+  fn to_point(&self) -> Point {
+    self.to_point_with_precision(TimeUnitV2::Nanosecond)
+  }
+}
+
+impl InfluxPoint for Point {
+  fn to_point_with_precision(&self, _precision: TimeUnitV2) -> Point {
+    self.clone()
+  }
+}
+
+/// Type of a point field, `None` leaves the field out.
+pub trait FieldValue {
+  fn set_field(&self, point: &mut Point, name: String);
+}
+
+macro_rules! impl_field_value {
+  ($setter:ident: $($ty:ty),*) => {
+    $(
+      impl FieldValue for $ty {
+        fn set_field(&self, point: &mut Point, name: String) {
+          point.$setter(name, (*self).into())
+        }
+      }
+    )*
+  };
+}
+
+impl_field_value!(set_float_field: f32, f64);
+impl_field_value!(set_int_field: i8, i16, i32, i64);
+impl_field_value!(set_uinteger_field: u8, u16, u32, u64);
+impl_field_value!(set_boolean_field: bool);
+
+impl FieldValue for str {
+  fn set_field(&self, point: &mut Point, name: String) {
+    point.set_string_field(name, self.to_string())
+  }
+}
+
+impl FieldValue for String {
+  fn set_field(&self, point: &mut Point, name: String) {
+    point.set_string_field(name, self.clone())
+  }
+}
+
+impl<T: FieldValue + ?Sized> FieldValue for &T {
+  fn set_field(&self, point: &mut Point, name: String) {
+    (**self).set_field(point, name)
+  }
+}
+
+impl<T: FieldValue> FieldValue for Option<T> {
+  fn set_field(&self, point: &mut Point, name: String) {
+    if let Some(value) = self {
+      value.set_field(point, name);
+    }
+  }
+}
+
+/// Type of a point tag, `None` leaves the tag out.
+pub trait TagValue {
+  fn tag_value(&self) -> Option<String>;
+}
+
+impl TagValue for str {
+  fn tag_value(&self) -> Option<String> {
+    Some(self.to_string())
+  }
+}
+
+impl TagValue for String {
+  fn tag_value(&self) -> Option<String> {
+    Some(self.clone())
+  }
+}
+
+impl<T: TagValue + ?Sized> TagValue for &T {
+  fn tag_value(&self) -> Option<String> {
+    (**self).tag_value()
+  }
+}
+
+impl<T: TagValue> TagValue for Option<T> {
+  fn tag_value(&self) -> Option<String> {
+    self.as_ref().and_then(TagValue::tag_value)
+  }
+}
+
+/// Type of a point timestamp in `precision`, the precision of the write. `None` leaves it to
+/// the server.
+pub trait TimestampValue {
+  fn timestamp_value(&self, precision: TimeUnitV2) -> Option<i64>;
+}
+
+macro_rules! impl_timestamp_value {
+  ($($ty:ty),*) => {
+    $(
+      /// Already in the precision of the write.
+      impl TimestampValue for $ty {
+        fn timestamp_value(&self, _precision: TimeUnitV2) -> Option<i64> {
+          Some((*self).into())
+        }
+      }
+    )*
+  };
+}
+
+impl_timestamp_value!(i32, i64, u32);
+
+/// Converted to the precision of the write, nanosecond dates after 2262 are left out.
+impl<Tz: TimeZone> TimestampValue for DateTime<Tz> {
+  fn timestamp_value(&self, precision: TimeUnitV2) -> Option<i64> {
+    match precision {
+      TimeUnitV2::Second => Some(self.timestamp()),
+      TimeUnitV2::Millisecond => Some(self.timestamp_millis()),
+      TimeUnitV2::Microsecond => Some(self.timestamp_micros()),
+      TimeUnitV2::Nanosecond => self.timestamp_nanos_opt(),
+    }
+  }
+}
+
+impl<T: TimestampValue + ?Sized> TimestampValue for &T {
+  fn timestamp_value(&self, precision: TimeUnitV2) -> Option<i64> {
+    (**self).timestamp_value(precision)
+  }
+}
+
+impl<T: TimestampValue> TimestampValue for Option<T> {
+  fn timestamp_value(&self, precision: TimeUnitV2) -> Option<i64> {
+    self
+      .as_ref()
+      .and_then(|value| value.timestamp_value(precision))
+  }
+}
+
+/// Converts a serializable struct or map into a point.
+///
+/// Members named in `tags` become tags, the member named `timestamp` becomes the timestamp and
+/// must be an integer in the precision of the write, all other members become fields. Null
+/// members are left out.
+pub fn to_point<T: Serialize>(
+  measurement: &str,
+  value: &T,
+  tags: &[&str],
+  timestamp: Option<&str>,
+) -> Result<Point> {
+  let members = match serde_json::to_value(value) {
+    Ok(serde_json::Value::Object(members)) => members,
+    Ok(_) => {
+      return Err(Error::InvalidInput(
+        "Only structs and maps can be written as points".to_string(),
+      ))
+    }
+    Err(e) => return Err(Error::InvalidInput(e.to_string())),
+  };
+
+  let mut point = Point::new(measurement.to_string());
+
+  for (name, value) in members {
+    if value.is_null() {
+      continue;
+    }
+
+    if timestamp == Some(name.as_str()) {
+      let time = value.as_i64().ok_or_else(|| {
+        Error::InvalidInput(format!("Timestamp '{name}' must be a 64-bit integer"))
+      })?;
+      point.set_timestamp(time);
+    } else if tags.contains(&name.as_str()) {
+      match value {
+        serde_json::Value::String(value) => point.set_tag(name, value),
+        value => point.set_tag(name, value.to_string()),
+      }
+    } else {
+      match value {
+        serde_json::Value::Bool(value) => point.set_boolean_field(name, value),
+        serde_json::Value::String(value) => point.set_string_field(name, value),
+        serde_json::Value::Number(number) => match number.as_i64() {
+          Some(value) => point.set_int_field(name, value),
+          None => point.set_float_field(name, number.as_f64().unwrap_or(f64::NAN)),
+        },
+        _ => {
+          return Err(Error::InvalidInput(format!(
+            "Field '{name}' must be a number, boolean or string"
+          )))
+        }
+      }
+    }
+  }
+
+  Ok(point)
+}
+
+/// Line protocol of `points`, points without fields are skipped. Derived timestamps are
+/// converted to the precision of the write.
+pub fn to_lines<P: InfluxPoint>(points: &[P], options: Option<&WriteOptions>) -> Vec<String> {
+  let precision = options
+    .and_then(|options| options.precision)
+    .map(|precision| match precision {
+      Precision::V2(unit) => unit,
+      Precision::V3(TimeUnitV3::Second) => TimeUnitV2::Second,
+      Precision::V3(TimeUnitV3::Millisecond) => TimeUnitV2::Millisecond,
+      Precision::V3(TimeUnitV3::Microsecond) => TimeUnitV2::Microsecond,
+      Precision::V3(TimeUnitV3::Nanosecond) => TimeUnitV2::Nanosecond,
+    });
+  let default_tags = options.and_then(|options| options.default_tags.clone());
+
+  points
+    .iter()
+    .filter_map(|point| {
+      point
+        .to_point_with_precision(precision.unwrap_or(TimeUnitV2::Nanosecond))
+        .to_line_protocol(precision, default_tags.clone())
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Clone, Serialize, InfluxPoint)]
+  #[influx(measurement = "cpu")]
+  struct Cpu<'a, T: FieldValue> {
+    /// Host the sample was taken on.
+    #[influx(tag)]
+    host: &'a str,
+    usage: f64,
+    #[influx(field, rename = "cores")]
+    #[serde(rename = "cores")]
+    core_count: Option<i64>,
+    #[influx(skip)]
+    #[serde(skip)]
+    extra: T,
+    #[influx(timestamp)]
+    time: i64,
+  }
+
+  #[test]
+  fn test_struct_points() {
+    let cpu = Cpu {
+      host: "a",
+      usage: 0.5,
+      core_count: Some(8),
+      extra: false,
+      time: 1_700_000_000_000_000_000,
+    };
+
+    let derived = cpu.to_point().to_line_protocol(None, None);
+    let serialized = to_point("cpu", &cpu, &["host"], Some("time"))
+      .unwrap()
+      .to_line_protocol(None, None);
+
+    assert_eq!(
+      derived.as_deref(),
+      Some("cpu,host=a cores=8i,usage=0.5 1700000000000000000")
+    );
+    assert_eq!(serialized, derived);
+  }
+
+  #[derive(InfluxPoint)]
+  #[influx(measurement = "mem")]
+  struct Mem {
+    used: u64,
+    #[influx(timestamp)]
+    time: DateTime<chrono::Utc>,
+  }
+
+  #[test]
+  fn test_timestamp_precisions() {
+    let mem = Mem {
+      used: u64::MAX,
+      time: DateTime::from_timestamp(1_700_000_000, 123_456_789).unwrap(),
+    };
+
+    for (precision, expected) in [
+      (
+        TimeUnitV2::Second,
+        "mem used=18446744073709551615u 1700000000",
+      ),
+      (
+        TimeUnitV2::Millisecond,
+        "mem used=18446744073709551615u 1700000000123",
+      ),
+      (
+        TimeUnitV2::Microsecond,
+        "mem used=18446744073709551615u 1700000000123456",
+      ),
+      (
+        TimeUnitV2::Nanosecond,
+        "mem used=18446744073709551615u 1700000000123456789",
+      ),
+    ] {
+      let options = WriteOptions {
+        precision: Some(Precision::V2(precision)),
+        ..Default::default()
+      };
+      assert_eq!(
+        to_lines(std::slice::from_ref(&mem), Some(&options)),
+        vec![expected.to_string()]
+      );
+    }
+  }
+}
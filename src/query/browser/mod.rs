@@ -1,2 +0,0 @@
-#[cfg(target_arch = "wasm32")]
-pub(crate) mod query_processor;
@@ -0,0 +1,295 @@
+use crate::client::options::{ExportFormat, ExportOptions, ParquetCompression};
+use crate::error::{Error, Result};
+use arrow::array::RecordBatch;
+use arrow::csv::{Writer as CsvWriter, WriterBuilder as CsvWriterBuilder};
+use arrow::datatypes::Schema;
+use arrow::error::ArrowError;
+use arrow::json::LineDelimitedWriter;
+use futures_util::{Stream, StreamExt};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Writes every batch of the query to `path` and returns the number of rows written.
+///
+/// The batches are written by a blocking task to a temporary file next to `path`, which replaces
+/// `path` once the query completed, so a failed export leaves no partial file behind. The
+/// temporary file is created before the first batch arrives, so an invalid path fails fast.
+pub(crate) async fn write_to_file<R>(
+  mut response: R,
+  path: &str,
+  format: ExportFormat,
+  options: ExportOptions,
+) -> Result<i64>
+where
+  R: Stream<Item = Result<RecordBatch>> + Unpin,
+{
+  let temp_path = temp_path(path);
+  let file = File::create(&temp_path)
+    .map_err(|e| Error::Request(format!("Failed to create '{path}': {e}")))?;
+
+  let (tx, mut rx) = tokio::sync::mpsc::channel::<RecordBatch>(EXPORT_QUEUE_SIZE);
+  let writer = tokio::task::spawn_blocking(move || {
+    let mut sink = FileSink::new(file, format, options);
+    while let Some(batch) = rx.blocking_recv() {
+      sink.write(&batch)?;
+    }
+    sink.finish()
+  });
+
+  let mut rows = 0i64;
+  let received = async {
+    while let Some(batch) = response.next().await {
+      let batch = batch?;
+      rows += batch.num_rows() as i64;
+      // The writer only hangs up after an error, which is returned below
+      if tx.send(batch).await.is_err() {
+        break;
+      }
+    }
+    Ok::<_, Error>(())
+  }
+  .await;
+  drop(tx);
+
+  let written = match writer.await {
+    Ok(written) => written.map_err(|e| Error::Request(format!("Failed to write '{path}': {e}"))),
+    Err(e) => Err(Error::Request(format!("Failed to write '{path}': {e}"))),
+  };
+
+  let result = written.and(received).and_then(|()| {
+    std::fs::rename(&temp_path, path)
+      .map_err(|e| Error::Request(format!("Failed to move export to '{path}': {e}")))
+  });
+
+  if result.is_err() {
+    let _ = std::fs::remove_file(&temp_path);
+  }
+
+  result.map(|()| rows)
+}
+
+/// Number of batches queued for the writer while it is busy.
+const EXPORT_QUEUE_SIZE: usize = 4;
+
+/// Unique path of the temporary file an export to `path` is written to, in the same directory so
+/// it can be renamed.
+fn temp_path(path: &str) -> PathBuf {
+  static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+  let path = Path::new(path);
+  let name = path.file_name().unwrap_or_default().to_string_lossy();
+  let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+  path.with_file_name(format!(".{name}.{}-{id}.tmp", std::process::id()))
+}
+
+enum FileSink {
+  /// The Parquet writer is created with the schema of the first batch. Queries without any
+  /// batch get a file without columns.
+  Parquet {
+    file: Option<File>,
+    properties: WriterProperties,
+    writer: Option<ArrowWriter<File>>,
+  },
+  Csv(CsvWriter<BufWriter<File>>),
+  Ndjson(LineDelimitedWriter<BufWriter<File>>),
+}
+
+impl FileSink {
+  fn new(file: File, format: ExportFormat, options: ExportOptions) -> Self {
+    match format {
+      ExportFormat::Parquet => Self::Parquet {
+        file: Some(file),
+        properties: writer_properties(&options),
+        writer: None,
+      },
+      ExportFormat::Csv => Self::Csv(
+        CsvWriterBuilder::new()
+          .with_header(options.header.unwrap_or(true))
+          .build(BufWriter::new(file)),
+      ),
+      ExportFormat::Ndjson => Self::Ndjson(LineDelimitedWriter::new(BufWriter::new(file))),
+    }
+  }
+
+  fn write(&mut self, batch: &RecordBatch) -> Result<(), ArrowError> {
+    match self {
+      Self::Parquet {
+        file,
+        properties,
+        writer,
+      } => {
+        if let Some(file) = file.take() {
+          *writer = Some(ArrowWriter::try_new(
+            file,
+            batch.schema(),
+            Some(properties.clone()),
+          )?);
+        }
+        match writer {
+          Some(writer) => Ok(writer.write(batch)?),
+          None => Ok(()),
+        }
+      }
+      Self::Csv(writer) => writer.write(batch),
+      Self::Ndjson(writer) => writer.write(batch),
+    }
+  }
+
+  fn finish(self) -> Result<(), ArrowError> {
+    match self {
+      Self::Parquet {
+        file,
+        properties,
+        writer,
+      } => {
+        let writer = match (writer, file) {
+          (Some(writer), _) => writer,
+          (None, Some(file)) => {
+            ArrowWriter::try_new(file, Arc::new(Schema::empty()), Some(properties))?
+          }
+          (None, None) => return Ok(()),
+        };
+        writer.close()?;
+        Ok(())
+      }
+      Self::Csv(writer) => Ok(writer.into_inner().flush()?),
+      Self::Ndjson(mut writer) => {
+        writer.finish()?;
+        Ok(writer.into_inner().flush()?)
+      }
+    }
+  }
+}
+
+fn writer_properties(options: &ExportOptions) -> WriterProperties {
+  let compression = match options.compression.unwrap_or_default() {
+    ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+    ParquetCompression::Snappy => Compression::SNAPPY,
+    ParquetCompression::Gzip => Compression::GZIP(GzipLevel::default()),
+    ParquetCompression::Lz4 => Compression::LZ4_RAW,
+    ParquetCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+  };
+
+  let mut builder = WriterProperties::builder().set_compression(compression);
+  if let Some(max_row_group_size) = options.max_row_group_size {
+    builder = builder.set_max_row_group_size(max_row_group_size.max(1) as usize);
+  }
+
+  builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::array::{Float64Array, StringArray};
+  use arrow::datatypes::{DataType, Field, Schema};
+  use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+  use std::sync::Arc;
+
+  fn batch() -> RecordBatch {
+    let schema = Arc::new(Schema::new(vec![
+      Field::new("host", DataType::Utf8, false),
+      Field::new("usage", DataType::Float64, false),
+    ]));
+    RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(StringArray::from(vec!["a", "b"])),
+        Arc::new(Float64Array::from(vec![0.5, 1.5])),
+      ],
+    )
+    .unwrap()
+  }
+
+  /// Path in the temp directory that parallel test runs don't share.
+  fn unique_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("influxdb3_napi_{}_{name}", std::process::id()))
+  }
+
+  async fn export(format: ExportFormat, name: &str) -> (i64, PathBuf) {
+    let path = unique_path(name);
+    let response = futures_util::stream::iter(vec![Ok(batch()), Ok(batch())]);
+    let rows = write_to_file(
+      response,
+      path.to_str().unwrap(),
+      format,
+      ExportOptions::default(),
+    )
+    .await
+    .unwrap();
+    (rows, path)
+  }
+
+  #[tokio::test]
+  async fn test_export_parquet() {
+    let (rows, path) = export(ExportFormat::Parquet, "export.parquet").await;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+      .unwrap()
+      .build()
+      .unwrap();
+    let read: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+
+    assert_eq!(rows, 4);
+    assert_eq!(read, 4);
+    std::fs::remove_file(path).unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_export_csv() {
+    let (rows, path) = export(ExportFormat::Csv, "export.csv").await;
+    let text = std::fs::read_to_string(&path).unwrap();
+
+    assert_eq!(rows, 4);
+    assert_eq!(text.lines().count(), 5);
+    assert_eq!(text.lines().next(), Some("host,usage"));
+    std::fs::remove_file(path).unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_export_empty_parquet() {
+    let path = unique_path("empty.parquet");
+    let empty = RecordBatch::new_empty(batch().schema());
+    let response = futures_util::stream::iter(vec![Ok(empty)]);
+
+    let rows = write_to_file(
+      response,
+      path.to_str().unwrap(),
+      ExportFormat::Parquet,
+      ExportOptions::default(),
+    )
+    .await
+    .unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+
+    assert_eq!(rows, 0);
+    assert_eq!(reader.schema().fields().len(), 2);
+    std::fs::remove_file(path).unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_failed_export_leaves_no_file() {
+    let path = unique_path("failed.csv");
+    let response = futures_util::stream::iter(vec![
+      Ok(batch()),
+      Err(Error::Request("connection reset".to_string())),
+    ]);
+
+    let result = write_to_file(
+      response,
+      path.to_str().unwrap(),
+      ExportFormat::Csv,
+      ExportOptions::default(),
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert!(!path.exists());
+  }
+}
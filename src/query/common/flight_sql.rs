@@ -0,0 +1,398 @@
+use crate::client::options::{QueryParamValue, QueryPayload};
+use crate::error::{Error, Result};
+use arrow::array::{
+  ArrayRef, BooleanArray, Float64Array, Int64Array, NullArray, RecordBatch, RecordBatchOptions,
+  StringArray, TimestampNanosecondArray,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{Field, Schema};
+use arrow::error::ArrowError;
+use arrow_flight::sql::client::{FlightSqlServiceClient, PreparedStatement};
+use arrow_flight::sql::CommandStatementQuery;
+use arrow_flight::{FlightInfo, Ticket};
+use futures_util::StreamExt;
+use reqwest::Url;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::Channel;
+
+/// Header InfluxDB 3 reads the target database of a Flight SQL request from.
+pub(crate) static DATABASE_HEADER: &str = "database";
+
+/// Location scheme telling clients to redeem a ticket on the connection of the plan.
+static REUSE_CONNECTION_SCHEME: &str = "arrow-flight-reuse-connection:";
+
+/// Returns a copy of the client that targets `database`, leaving the shared client untouched.
+pub(crate) fn with_database(
+  client: &FlightSqlServiceClient<Channel>,
+  database: &str,
+) -> FlightSqlServiceClient<Channel> {
+  let mut client = client.clone();
+  client.set_header(DATABASE_HEADER, database);
+  client
+}
+
+/// Runs the query as a Flight SQL `CommandStatementQuery`: `GetFlightInfo` plans it, then every
+/// returned endpoint is fetched with `DoGet`, in order.
+///
+/// `origin` is the address the client's channel connects to, see [`endpoint_tickets`].
+pub(crate) async fn query_flight_sql(
+  client: &FlightSqlServiceClient<Channel>,
+  origin: &str,
+  query_payload: QueryPayload,
+) -> Result<ReceiverStream<Result<RecordBatch>>> {
+  if query_payload.params.is_some() {
+    return Err(Error::Request(
+      "Flight SQL statements do not accept parameters, use a prepared statement instead"
+        .to_string(),
+    ));
+  }
+
+  let mut client = with_database(client, &query_payload.database);
+
+  let flight_info = client
+    .get_flight_info_for_command(statement_command(query_payload.query))
+    .await
+    .map_err(|e| Error::Request(format!("Flight SQL request failed: {e}")))?;
+
+  fetch_endpoints(client, origin, flight_info)
+}
+
+fn statement_command(query: String) -> CommandStatementQuery {
+  CommandStatementQuery {
+    query,
+    transaction_id: None,
+  }
+}
+
+/// Streams the batches of every endpoint in `flight_info`, in order, on the channel the plan
+/// came from. Fails before fetching anything if an endpoint is served elsewhere.
+pub(crate) fn fetch_endpoints(
+  mut client: FlightSqlServiceClient<Channel>,
+  origin: &str,
+  flight_info: FlightInfo,
+) -> Result<ReceiverStream<Result<RecordBatch>>> {
+  let tickets = endpoint_tickets(&flight_info, origin)?;
+  let schema = flight_info.try_decode_schema().ok();
+  let (tx, rx) = tokio::sync::mpsc::channel::<Result<RecordBatch>>(100);
+
+  tokio::spawn(async move {
+    let mut received = false;
+
+    for ticket in tickets {
+      let mut response = match client.do_get(ticket).await {
+        Ok(response) => response,
+        Err(e) => {
+          let _ = tx.send(Err(e.into())).await;
+          return;
+        }
+      };
+
+      while let Some(batch) = response.next().await {
+        received = true;
+        if tx.send(batch.map_err(Error::from)).await.is_err() {
+          return;
+        }
+      }
+    }
+
+    // An empty result still carries the columns of the plan
+    if let (false, Some(schema)) = (received, schema) {
+      let _ = tx.send(Ok(RecordBatch::new_empty(Arc::new(schema)))).await;
+    }
+  });
+
+  Ok(ReceiverStream::new(rx))
+}
+
+/// Tickets of the endpoints in `flight_info`, in order.
+///
+/// An endpoint without locations, with the reuse-connection location or with a location on the
+/// host and port of `origin` is redeemed on the current channel. Any other location would need
+/// a connection of its own, which isn't supported, so it is rejected.
+pub(crate) fn endpoint_tickets(flight_info: &FlightInfo, origin: &str) -> Result<Vec<Ticket>> {
+  let origin = Url::parse(origin).ok();
+  let mut tickets = Vec::with_capacity(flight_info.endpoint.len());
+
+  for endpoint in &flight_info.endpoint {
+    let Some(ticket) = &endpoint.ticket else {
+      continue;
+    };
+
+    let local = endpoint.location.is_empty()
+      || endpoint
+        .location
+        .iter()
+        .any(|location| is_origin(&location.uri, origin.as_ref()));
+    if !local {
+      let locations: Vec<_> = endpoint
+        .location
+        .iter()
+        .map(|location| location.uri.as_str())
+        .collect();
+      return Err(Error::Request(format!(
+        "Flight endpoint is served by {}, only endpoints of the connected server are supported",
+        locations.join(", ")
+      )));
+    }
+
+    tickets.push(ticket.clone());
+  }
+
+  Ok(tickets)
+}
+
+fn is_origin(uri: &str, origin: Option<&Url>) -> bool {
+  if uri.starts_with(REUSE_CONNECTION_SCHEME) {
+    return true;
+  }
+
+  match (Url::parse(uri), origin) {
+    (Ok(location), Some(origin)) => {
+      location.host_str() == origin.host_str() && default_port(&location) == default_port(origin)
+    }
+    _ => false,
+  }
+}
+
+fn default_port(url: &Url) -> Option<u16> {
+  url.port().or(match url.scheme() {
+    "https" | "grpc+tls" => Some(443),
+    "http" | "grpc" | "grpc+tcp" => Some(80),
+    _ => None,
+  })
+}
+
+/// Creates a server side prepared statement for `query` on `database`.
+pub(crate) async fn prepare_statement(
+  client: &FlightSqlServiceClient<Channel>,
+  database: &str,
+  query: String,
+) -> Result<(FlightSqlServiceClient<Channel>, PreparedStatement<Channel>)> {
+  let mut client = with_database(client, database);
+
+  let statement = client
+    .prepare(query, None)
+    .await
+    .map_err(|e| Error::Request(format!("Failed to prepare statement: {e}")))?;
+
+  Ok((client, statement))
+}
+
+/// Binds `params` to the prepared statement and streams the result of executing it on `origin`.
+///
+/// `bound` tracks whether an earlier execution bound parameters, executing without `params`
+/// then replaces them with an empty binding instead of reusing them.
+pub(crate) async fn execute_prepared(
+  client: &FlightSqlServiceClient<Channel>,
+  origin: &str,
+  statement: &mut PreparedStatement<Channel>,
+  params: Option<HashMap<String, QueryParamValue>>,
+  bound: &mut bool,
+) -> Result<ReceiverStream<Result<RecordBatch>>> {
+  if params.is_some() || *bound {
+    let parameter_schema = statement.parameter_schema().ok().cloned();
+    let has_params = params.is_some();
+    let batch = parameter_batch(parameter_schema.as_ref(), params.unwrap_or_default())
+      .map_err(|e| Error::InvalidInput(format!("Invalid statement parameters: {e}")))?;
+
+    statement
+      .set_parameters(batch)
+      .map_err(|e| Error::InvalidInput(format!("Invalid statement parameters: {e}")))?;
+    *bound = has_params;
+  }
+
+  let flight_info = statement
+    .execute()
+    .await
+    .map_err(|e| Error::Request(format!("Failed to execute statement: {e}")))?;
+
+  fetch_endpoints(client.clone(), origin, flight_info)
+}
+
+/// Builds the single row parameter batch for a prepared statement.
+///
+/// Columns follow the parameter schema announced by the server and are cast to its types.
+/// Without a schema the parameters are sent in name order with their natural Arrow types.
+/// Parameters the schema doesn't name are rejected.
+pub(crate) fn parameter_batch(
+  parameter_schema: Option<&Schema>,
+  mut params: HashMap<String, QueryParamValue>,
+) -> Result<RecordBatch, ArrowError> {
+  let names: Vec<String> = match parameter_schema {
+    Some(schema) if !schema.fields().is_empty() => schema
+      .fields()
+      .iter()
+      .map(|field| field.name().clone())
+      .collect(),
+    _ => {
+      let mut names: Vec<String> = params.keys().cloned().collect();
+      names.sort();
+      names
+    }
+  };
+
+  let mut fields = Vec::with_capacity(names.len());
+  let mut columns = Vec::with_capacity(names.len());
+
+  for name in names {
+    let value = params
+      .remove(&name)
+      .or_else(|| params.remove(name.trim_start_matches('$')))
+      .ok_or_else(|| ArrowError::InvalidArgumentError(format!("Missing parameter '{name}'")))?;
+
+    let mut column = parameter_array(value);
+
+    let field = match parameter_schema.and_then(|schema| schema.field_with_name(&name).ok()) {
+      Some(field) => {
+        column = cast(&column, field.data_type())?;
+        field.clone()
+      }
+      None => Field::new(name, column.data_type().clone(), true),
+    };
+
+    fields.push(field);
+    columns.push(column);
+  }
+
+  if !params.is_empty() {
+    let mut unknown: Vec<_> = params.into_keys().collect();
+    unknown.sort();
+    return Err(ArrowError::InvalidArgumentError(format!(
+      "Unknown parameters: {}",
+      unknown.join(", ")
+    )));
+  }
+
+  // A statement without parameters is bound to a single row without columns
+  RecordBatch::try_new_with_options(
+    Arc::new(Schema::new(fields)),
+    columns,
+    &RecordBatchOptions::new().with_row_count(Some(1)),
+  )
+}
+
+fn parameter_array(value: QueryParamValue) -> ArrayRef {
+  match value {
+    QueryParamValue::Int(i) => Arc::new(Int64Array::from(vec![i])),
+    QueryParamValue::Float(f) => Arc::new(Float64Array::from(vec![f])),
+    QueryParamValue::Bool(b) => Arc::new(BooleanArray::from(vec![b])),
+    QueryParamValue::String(s) => Arc::new(StringArray::from(vec![s])),
+    QueryParamValue::Timestamp(nanos) => {
+      Arc::new(TimestampNanosecondArray::from(vec![nanos]).with_timezone("UTC"))
+    }
+    QueryParamValue::Null => Arc::new(NullArray::new(1)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::datatypes::DataType;
+  use arrow_flight::sql::ProstMessageExt;
+  use arrow_flight::FlightEndpoint;
+
+  #[test]
+  fn test_statement_command() {
+    let command = statement_command("SELECT 1".to_string()).as_any();
+
+    assert_eq!(
+      command.type_url,
+      "type.googleapis.com/arrow.flight.protocol.sql.CommandStatementQuery"
+    );
+    let command: CommandStatementQuery = command.unpack().unwrap().unwrap();
+    assert_eq!(command.query, "SELECT 1");
+    assert_eq!(command.transaction_id, None);
+  }
+
+  #[test]
+  fn test_endpoint_tickets() {
+    let flight_info = FlightInfo::new()
+      .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new("a")))
+      .with_endpoint(FlightEndpoint::new())
+      .with_endpoint(
+        FlightEndpoint::new()
+          .with_ticket(Ticket::new("b"))
+          .with_location("arrow-flight-reuse-connection://?"),
+      )
+      .with_endpoint(
+        FlightEndpoint::new()
+          .with_ticket(Ticket::new("c"))
+          .with_location("grpc+tls://localhost:8181"),
+      );
+
+    let tickets = endpoint_tickets(&flight_info, "https://localhost:8181").unwrap();
+    assert_eq!(
+      tickets,
+      vec![Ticket::new("a"), Ticket::new("b"), Ticket::new("c")]
+    );
+
+    let elsewhere = flight_info.with_endpoint(
+      FlightEndpoint::new()
+        .with_ticket(Ticket::new("d"))
+        .with_location("grpc+tcp://replica:8181"),
+    );
+    let error = endpoint_tickets(&elsewhere, "https://localhost:8181").unwrap_err();
+    assert!(error.to_string().contains("grpc+tcp://replica:8181"));
+  }
+
+  #[test]
+  fn test_parameter_batch_follows_schema() {
+    let schema = Schema::new(vec![
+      Field::new("$2", DataType::Utf8, true),
+      Field::new("$1", DataType::Float64, true),
+    ]);
+    let params = HashMap::from([
+      ("1".to_string(), QueryParamValue::Int(10)),
+      ("$2".to_string(), QueryParamValue::from("host-a")),
+    ]);
+
+    let batch = parameter_batch(Some(&schema), params).unwrap();
+
+    assert_eq!(batch.num_rows(), 1);
+    assert_eq!(batch.schema().field(0).name(), "$2");
+    assert_eq!(batch.column(1).data_type(), &DataType::Float64);
+  }
+
+  #[test]
+  fn test_parameter_batch_without_schema() {
+    let params = HashMap::from([
+      ("b".to_string(), QueryParamValue::Bool(true)),
+      ("a".to_string(), QueryParamValue::Int(1)),
+    ]);
+
+    let batch = parameter_batch(None, params).unwrap();
+
+    assert_eq!(batch.schema().field(0).name(), "a");
+    assert_eq!(batch.column(0).data_type(), &DataType::Int64);
+    assert_eq!(batch.column(1).data_type(), &DataType::Boolean);
+  }
+
+  #[test]
+  fn test_parameter_batch_missing_parameter() {
+    let schema = Schema::new(vec![Field::new("$1", DataType::Int64, true)]);
+    assert!(parameter_batch(Some(&schema), HashMap::new()).is_err());
+  }
+
+  #[test]
+  fn test_parameter_batch_unknown_parameter() {
+    let schema = Schema::new(vec![Field::new("$1", DataType::Int64, true)]);
+    let params = HashMap::from([
+      ("1".to_string(), QueryParamValue::Int(1)),
+      ("host".to_string(), QueryParamValue::from("a")),
+    ]);
+
+    let error = parameter_batch(Some(&schema), params).unwrap_err();
+
+    assert!(error.to_string().contains("Unknown parameters: host"));
+  }
+
+  #[test]
+  fn test_parameter_batch_empty() {
+    let batch = parameter_batch(None, HashMap::new()).unwrap();
+
+    assert_eq!(batch.num_columns(), 0);
+    assert_eq!(batch.num_rows(), 1);
+  }
+}
@@ -0,0 +1,301 @@
+use crate::client::base::HttpCore;
+use crate::client::options::{HttpQueryFormat, QueryPayload};
+use crate::error::{Error, Result};
+use arrow::array::RecordBatch;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatchReader;
+use futures_util::StreamExt;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use reqwest::Response;
+use std::io::Cursor;
+use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::codegen::Bytes;
+
+/// Runs the query with the HTTP query API of `http` and decodes the response body into record
+/// batches, so it can go through the same serializers as a Flight response.
+pub(crate) async fn query_http(
+  http: &HttpCore,
+  query_payload: QueryPayload,
+) -> Result<ReceiverStream<Result<RecordBatch>>> {
+  let format = query_payload.format.unwrap_or_default();
+  let inference_rows = query_payload
+    .schema_inference_rows
+    .map_or(HTTP_BATCH_ROWS, |rows| rows.max(1) as usize);
+  let response = http.query(query_payload).await?;
+
+  let (tx, rx) = tokio::sync::mpsc::channel::<Result<RecordBatch>>(100);
+
+  tokio::spawn(async move {
+    let result = match format {
+      HttpQueryFormat::Parquet => decode_parquet(response, &tx).await,
+      _ => decode_lines(response, format, inference_rows, &tx).await,
+    };
+
+    if let Err(e) = result {
+      let _ = tx.send(Err(e)).await;
+    }
+  });
+
+  Ok(ReceiverStream::new(rx))
+}
+
+/// Splits the line based formats into batches of `HTTP_BATCH_ROWS` rows while the body is still
+/// being received.
+///
+/// The schema is inferred once from the first `inference_rows` rows, so every batch has the same
+/// columns and types. JSON rows omit their null columns, those are filled with nulls, while
+/// columns that only appear after the inferred rows are left out. Integer columns are widened to
+/// floats, as a later row may hold a fraction.
+async fn decode_lines(
+  response: Response,
+  format: HttpQueryFormat,
+  inference_rows: usize,
+  tx: &tokio::sync::mpsc::Sender<Result<RecordBatch>>,
+) -> Result<()> {
+  let mut body = response.bytes_stream();
+  let mut schema: Option<SchemaRef> = None;
+  let mut header: Option<Vec<u8>> = None;
+  let mut partial: Vec<u8> = Vec::new();
+  let mut lines: Vec<u8> = Vec::new();
+  let mut line_count = 0;
+
+  while let Some(chunk) = body.next().await {
+    let chunk = chunk.map_err(|e| Error::Transport(format!("HTTP query response failed: {e}")))?;
+    partial.extend_from_slice(&chunk);
+
+    let Some(last_newline) = partial.iter().rposition(|byte| *byte == b'\n') else {
+      continue;
+    };
+
+    let complete: Vec<u8> = partial.drain(..=last_newline).collect();
+    for line in complete.split_inclusive(|byte| *byte == b'\n') {
+      if format == HttpQueryFormat::Csv && header.is_none() {
+        header = Some(line.to_vec());
+      } else if !line.trim_ascii().is_empty() {
+        lines.extend_from_slice(line);
+        line_count += 1;
+      }
+    }
+
+    let batch_rows = match schema {
+      Some(_) => HTTP_BATCH_ROWS,
+      None => inference_rows,
+    };
+    if line_count >= batch_rows {
+      send_batches(
+        decode_text(&lines, header.as_deref(), format, &mut schema)?,
+        tx,
+      )
+      .await;
+      lines.clear();
+      line_count = 0;
+    }
+  }
+
+  if !partial.trim_ascii().is_empty() {
+    if format == HttpQueryFormat::Csv && header.is_none() {
+      return Ok(());
+    }
+    lines.extend_from_slice(&partial);
+    lines.push(b'\n');
+    line_count += 1;
+  }
+
+  if line_count > 0 {
+    send_batches(
+      decode_text(&lines, header.as_deref(), format, &mut schema)?,
+      tx,
+    )
+    .await;
+  }
+
+  Ok(())
+}
+
+/// Decodes complete lines with `schema`, which is inferred from `lines` when there is none yet.
+fn decode_text(
+  lines: &[u8],
+  header: Option<&[u8]>,
+  format: HttpQueryFormat,
+  schema: &mut Option<SchemaRef>,
+) -> Result<Vec<RecordBatch>, ArrowError> {
+  match format {
+    HttpQueryFormat::Csv => {
+      let data = [header.unwrap_or_default(), lines].concat();
+      let schema = match schema {
+        Some(schema) => schema.clone(),
+        None => {
+          let (inferred, _) = arrow::csv::reader::Format::default()
+            .with_header(true)
+            .infer_schema(Cursor::new(&data), None)?;
+          schema.insert(widen_types(inferred)).clone()
+        }
+      };
+
+      arrow::csv::ReaderBuilder::new(schema)
+        .with_header(true)
+        .with_batch_size(HTTP_BATCH_ROWS)
+        .build(Cursor::new(&data))?
+        .collect()
+    }
+    _ => {
+      let schema = match schema {
+        Some(schema) => schema.clone(),
+        None => {
+          let (inferred, _) = arrow::json::reader::infer_json_schema(Cursor::new(lines), None)?;
+          schema.insert(widen_types(inferred)).clone()
+        }
+      };
+
+      // Numbers in a column inferred as text, e.g. a null one, are kept as strings
+      arrow::json::ReaderBuilder::new(schema)
+        .with_batch_size(HTTP_BATCH_ROWS)
+        .with_coerce_primitive(true)
+        .build(Cursor::new(lines))?
+        .collect()
+    }
+  }
+}
+
+/// Widens the inferred types so the values of later rows still decode: columns that were null in
+/// every inferred row become strings and integer columns become floats. The `time` column becomes
+/// a nanosecond timestamp, like in Flight responses.
+fn widen_types(schema: Schema) -> SchemaRef {
+  let fields: Vec<Field> = schema
+    .fields()
+    .iter()
+    .map(|field| {
+      let data_type = match field.data_type() {
+        _ if field.name() == TIME_COLUMN => DataType::Timestamp(TimeUnit::Nanosecond, None),
+        DataType::Null => DataType::Utf8,
+        DataType::Int64 => DataType::Float64,
+        data_type => data_type.clone(),
+      };
+      field.as_ref().clone().with_data_type(data_type)
+    })
+    .collect();
+
+  Arc::new(Schema::new(fields))
+}
+
+async fn decode_parquet(
+  response: Response,
+  tx: &tokio::sync::mpsc::Sender<Result<RecordBatch>>,
+) -> Result<()> {
+  let body: Bytes = response
+    .bytes()
+    .await
+    .map_err(|e| Error::Transport(format!("HTTP query response failed: {e}")))?;
+
+  if body.is_empty() {
+    return Ok(());
+  }
+
+  let reader = ParquetRecordBatchReaderBuilder::try_new(body)
+    .and_then(|builder| builder.with_batch_size(HTTP_BATCH_ROWS).build())
+    .map_err(|e| Error::Request(format!("Invalid Parquet response: {e}")))?;
+
+  let schema = reader.schema();
+  let mut batches = reader.collect::<Result<Vec<_>, ArrowError>>()?;
+  // An empty result still carries its columns
+  if batches.is_empty() {
+    batches.push(RecordBatch::new_empty(schema));
+  }
+  send_batches(batches, tx).await;
+
+  Ok(())
+}
+
+async fn send_batches(
+  batches: Vec<RecordBatch>,
+  tx: &tokio::sync::mpsc::Sender<Result<RecordBatch>>,
+) {
+  for batch in batches {
+    if tx.send(Ok(batch)).await.is_err() {
+      break;
+    }
+  }
+}
+const HTTP_BATCH_ROWS: usize = 1024;
+const TIME_COLUMN: &str = "time";
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::array::AsArray;
+  use arrow::datatypes::{Float64Type, TimestampNanosecondType};
+
+  #[test]
+  fn test_decode_text() {
+    let jsonl = b"{\"host\":\"a\",\"usage\":0.5}\n{\"host\":\"b\",\"usage\":1.5}\n";
+    let batches = decode_text(jsonl, None, HttpQueryFormat::Jsonl, &mut None).unwrap();
+    assert_eq!(batches[0].num_rows(), 2);
+    assert_eq!(batches[0].num_columns(), 2);
+
+    let csv = b"a,1.5\nb,2.5\n";
+    let header = Some(b"host,usage\n".as_slice());
+    let batches = decode_text(csv, header, HttpQueryFormat::Csv, &mut None).unwrap();
+    assert_eq!(batches[0].num_rows(), 2);
+    assert_eq!(batches[0].schema().field(0).name(), "host");
+  }
+
+  #[test]
+  fn test_schema_is_inferred_once() {
+    let mut schema = None;
+    let first = b"{\"host\":\"a\",\"usage\":0.5,\"region\":null}\n";
+    let second = b"{\"host\":\"b\",\"region\":\"eu\"}\n";
+
+    let first = decode_text(first, None, HttpQueryFormat::Jsonl, &mut schema).unwrap();
+    let second = decode_text(second, None, HttpQueryFormat::Jsonl, &mut schema).unwrap();
+
+    assert_eq!(first[0].schema(), second[0].schema());
+    assert!(second[0].column_by_name("usage").unwrap().is_null(0));
+    assert_eq!(
+      second[0].column_by_name("region").unwrap().data_type(),
+      &DataType::Utf8
+    );
+  }
+
+  #[test]
+  fn test_integers_become_floats() {
+    let mut schema = None;
+    let first = b"{\"usage\":1}\n";
+    let second = b"{\"usage\":1.5}\n";
+
+    decode_text(first, None, HttpQueryFormat::Jsonl, &mut schema).unwrap();
+    let second = decode_text(second, None, HttpQueryFormat::Jsonl, &mut schema).unwrap();
+    let usage = second[0].column_by_name("usage").unwrap();
+    assert_eq!(usage.data_type(), &DataType::Float64);
+    assert_eq!(usage.as_primitive::<Float64Type>().value(0), 1.5);
+
+    let mut schema = None;
+    let header = Some(b"usage\n".as_slice());
+    decode_text(b"1\n", header, HttpQueryFormat::Csv, &mut schema).unwrap();
+    let second = decode_text(b"1.5\n", header, HttpQueryFormat::Csv, &mut schema).unwrap();
+    let usage = second[0].column_by_name("usage").unwrap();
+    assert_eq!(usage.as_primitive::<Float64Type>().value(0), 1.5);
+  }
+
+  #[test]
+  fn test_time_is_timestamp() {
+    let jsonl = b"{\"time\":\"2024-01-01T00:00:00.000000001\",\"usage\":0.5}\n";
+    let batches = decode_text(jsonl, None, HttpQueryFormat::Jsonl, &mut None).unwrap();
+    let csv = b"2024-01-01T00:00:00.000000001,0.5\n";
+    let header = Some(b"time,usage\n".as_slice());
+    let csv_batches = decode_text(csv, header, HttpQueryFormat::Csv, &mut None).unwrap();
+
+    for batch in [&batches[0], &csv_batches[0]] {
+      let time = batch.column_by_name("time").unwrap();
+      assert_eq!(
+        time.data_type(),
+        &DataType::Timestamp(TimeUnit::Nanosecond, None)
+      );
+      assert_eq!(
+        time.as_primitive::<TimestampNanosecondType>().value(0),
+        1_704_067_200_000_000_001
+      );
+    }
+  }
+}
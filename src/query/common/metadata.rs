@@ -0,0 +1,308 @@
+use crate::error::{Error, Result};
+use crate::query::common::flight_sql::{fetch_endpoints, with_database};
+use arrow::array::{Array, AsArray, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::ArrowError;
+use arrow::ipc::convert::try_schema_from_ipc_buffer;
+use arrow_flight::sql::client::FlightSqlServiceClient;
+use arrow_flight::sql::CommandGetTables;
+use futures_util::StreamExt;
+#[cfg(feature = "napi")]
+use napi_derive::napi;
+use reqwest::{Client, StatusCode, Url};
+use tonic::transport::Channel;
+
+/// Role of a column in an InfluxDB table.
+#[cfg_attr(feature = "napi", napi(string_enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+  #[cfg_attr(feature = "napi", napi(value = "tag"))]
+  Tag,
+  #[cfg_attr(feature = "napi", napi(value = "field"))]
+  Field,
+  #[cfg_attr(feature = "napi", napi(value = "time"))]
+  Time,
+}
+
+#[cfg_attr(not(feature = "native"), napi(object))]
+#[derive(Debug, Clone)]
+pub struct ColumnDescription {
+  pub name: String,
+  pub kind: ColumnKind,
+  /** Arrow data type, e.g. `Dictionary(Int32, Utf8)` or `Float64` */
+  pub data_type: String,
+  pub nullable: bool,
+}
+
+#[cfg_attr(not(feature = "native"), napi(object))]
+#[derive(Debug, Clone)]
+pub struct TableDescription {
+  pub database: String,
+  pub table: String,
+  pub columns: Vec<ColumnDescription>,
+}
+
+/// Lists the databases of an InfluxDB 3 Core or Enterprise server.
+///
+/// Only those servers have an API for it. Flight SQL `GetCatalogs` is no fallback, InfluxDB 3
+/// answers it with the `public` catalog of the queried database instead of the databases.
+pub(crate) async fn list_databases(http_client: &Client, addr: &str) -> Result<Vec<String>> {
+  let url = Url::parse_with_params(
+    format!("{addr}{DATABASES_PATH}").as_str(),
+    &[("format", "json")],
+  )
+  .map_err(|e| Error::Request(format!("Error parsing URL: {e}")))?;
+
+  let response = http_client
+    .get(url)
+    .send()
+    .await
+    .map_err(|e| Error::Transport(format!("HTTP request failed: {e}")))?;
+
+  let status = response.status();
+  if !status.is_success() {
+    let message = response.text().await.unwrap_or_default();
+    return Err(databases_error(status, message));
+  }
+
+  let rows: Vec<serde_json::Map<String, serde_json::Value>> = response
+    .json()
+    .await
+    .map_err(|e| Error::Request(format!("Failed to parse JSON: {e}")))?;
+
+  Ok(
+    rows
+      .iter()
+      .filter_map(|row| row.get(DATABASE_COLUMN).and_then(|name| name.as_str()))
+      .map(String::from)
+      .collect(),
+  )
+}
+
+/// Error of an unsuccessful `list_databases` response, a server without the endpoint is not
+/// InfluxDB 3 Core or Enterprise.
+fn databases_error(status: StatusCode, message: String) -> Error {
+  match status {
+    StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => Error::Request(format!(
+      "Listing databases is only supported by InfluxDB 3 Core and Enterprise, the server has no \
+       {DATABASES_PATH} endpoint"
+    )),
+    status => Error::from_response(status, message),
+  }
+}
+
+/// Lists the user tables of `database` with Flight SQL `GetTables`, system tables are left out.
+pub(crate) async fn list_tables(
+  client: &FlightSqlServiceClient<Channel>,
+  origin: &str,
+  database: &str,
+) -> Result<Vec<String>> {
+  let tables = get_tables(client, origin, database, None).await?;
+
+  Ok(tables.into_iter().map(|(name, _)| name).collect())
+}
+
+/// Describes the columns of `table` in `database` from the schema returned by `GetTables`.
+pub(crate) async fn describe_table(
+  client: &FlightSqlServiceClient<Channel>,
+  origin: &str,
+  database: &str,
+  table: &str,
+) -> Result<TableDescription> {
+  let schema = get_tables(client, origin, database, Some(table))
+    .await?
+    .into_iter()
+    .find(|(name, _)| name == table)
+    .and_then(|(_, schema)| schema)
+    .ok_or_else(|| Error::Request(format!("Table '{table}' not found in '{database}'")))?;
+
+  Ok(TableDescription {
+    database: database.to_string(),
+    table: table.to_string(),
+    columns: describe_schema(&schema),
+  })
+}
+
+pub(crate) fn describe_schema(schema: &Schema) -> Vec<ColumnDescription> {
+  schema
+    .fields()
+    .iter()
+    .map(|field| ColumnDescription {
+      name: field.name().clone(),
+      kind: column_kind(field),
+      data_type: field.data_type().to_string(),
+      nullable: field.is_nullable(),
+    })
+    .collect()
+}
+
+/// Prefers the column type InfluxDB stores in the field metadata and falls back to the
+/// conventions of its schemas: dictionary encoded strings are tags, `time` is the timestamp.
+fn column_kind(field: &Field) -> ColumnKind {
+  match field
+    .metadata()
+    .get(IOX_COLUMN_TYPE_KEY)
+    .map(String::as_str)
+  {
+    Some(IOX_COLUMN_TYPE_TAG) => return ColumnKind::Tag,
+    Some(IOX_COLUMN_TYPE_TIMESTAMP) => return ColumnKind::Time,
+    Some(_) => return ColumnKind::Field,
+    None => {}
+  }
+
+  match field.data_type() {
+    DataType::Dictionary(_, value_type) if **value_type == DataType::Utf8 => ColumnKind::Tag,
+    DataType::Timestamp(_, _) if field.name() == "time" => ColumnKind::Time,
+    _ => ColumnKind::Field,
+  }
+}
+
+async fn get_tables(
+  client: &FlightSqlServiceClient<Channel>,
+  origin: &str,
+  database: &str,
+  table: Option<&str>,
+) -> Result<Vec<(String, Option<Schema>)>> {
+  let mut client = with_database(client, database);
+
+  let request = CommandGetTables {
+    catalog: None,
+    db_schema_filter_pattern: Some(IOX_SCHEMA.to_string()),
+    table_name_filter_pattern: table.map(escape_pattern),
+    table_types: vec![],
+    include_schema: table.is_some(),
+  };
+
+  let flight_info = client
+    .get_tables(request)
+    .await
+    .map_err(|e| Error::Request(format!("Flight SQL request failed: {e}")))?;
+
+  let mut response = fetch_endpoints(client, origin, flight_info)?;
+  let mut tables = Vec::new();
+
+  while let Some(batch) = response.next().await {
+    tables.extend(
+      read_tables(&batch?)
+        .map_err(|e| Error::Request(format!("Invalid GetTables response: {e}")))?,
+    );
+  }
+
+  Ok(tables)
+}
+
+/// Escapes the wildcards of a `LIKE` filter pattern, so `table` only matches itself.
+fn escape_pattern(table: &str) -> String {
+  let mut pattern = String::with_capacity(table.len());
+  for c in table.chars() {
+    if matches!(c, '\\' | '_' | '%') {
+      pattern.push('\\');
+    }
+    pattern.push(c);
+  }
+  pattern
+}
+
+fn read_tables(batch: &RecordBatch) -> Result<Vec<(String, Option<Schema>)>, ArrowError> {
+  let Some(names) = batch.column_by_name("table_name") else {
+    return Ok(vec![]);
+  };
+  let names = cast(names, &DataType::Utf8)?;
+  let names = names.as_string::<i32>();
+  let schemas = batch
+    .column_by_name("table_schema")
+    .and_then(|schemas| schemas.as_binary_opt::<i32>());
+
+  let mut tables = Vec::with_capacity(batch.num_rows());
+
+  for row in 0..batch.num_rows() {
+    if names.is_null(row) {
+      continue;
+    }
+
+    let schema = match schemas {
+      Some(schemas) if !schemas.is_null(row) => {
+        Some(try_schema_from_ipc_buffer(schemas.value(row))?)
+      }
+      _ => None,
+    };
+
+    tables.push((names.value(row).to_string(), schema));
+  }
+
+  Ok(tables)
+}
+
+const DATABASES_PATH: &str = "/api/v3/configure/database";
+const DATABASE_COLUMN: &str = "iox::database";
+const IOX_SCHEMA: &str = "iox";
+const IOX_COLUMN_TYPE_KEY: &str = "iox::column::type";
+const IOX_COLUMN_TYPE_TAG: &str = "iox::column_type::tag";
+const IOX_COLUMN_TYPE_TIMESTAMP: &str = "iox::column_type::timestamp";
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::datatypes::TimeUnit;
+  use std::collections::HashMap;
+
+  #[test]
+  fn test_describe_schema() {
+    let schema = Schema::new(vec![
+      Field::new(
+        "host",
+        DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+        true,
+      ),
+      Field::new("usage", DataType::Float64, true),
+      Field::new(
+        "time",
+        DataType::Timestamp(TimeUnit::Nanosecond, None),
+        false,
+      ),
+      Field::new("region", DataType::Utf8, true).with_metadata(HashMap::from([(
+        IOX_COLUMN_TYPE_KEY.to_string(),
+        IOX_COLUMN_TYPE_TAG.to_string(),
+      )])),
+    ]);
+
+    let kinds: Vec<ColumnKind> = describe_schema(&schema)
+      .into_iter()
+      .map(|column| column.kind)
+      .collect();
+
+    assert_eq!(
+      kinds,
+      vec![
+        ColumnKind::Tag,
+        ColumnKind::Field,
+        ColumnKind::Time,
+        ColumnKind::Tag
+      ]
+    );
+    assert_eq!(describe_schema(&schema)[1].data_type, "Float64");
+  }
+
+  #[test]
+  fn test_databases_error() {
+    let missing = databases_error(StatusCode::NOT_FOUND, String::new());
+    assert!(matches!(missing, Error::Request(message) if message.contains(DATABASES_PATH)));
+
+    let failed = databases_error(StatusCode::INTERNAL_SERVER_ERROR, "boom".to_string());
+    assert_eq!(
+      failed,
+      Error::Server {
+        status: 500,
+        message: "boom".to_string()
+      }
+    );
+  }
+
+  #[test]
+  fn test_escape_pattern() {
+    assert_eq!(escape_pattern("cpu"), "cpu");
+    assert_eq!(escape_pattern("cpu_total%"), "cpu\\_total\\%");
+    assert_eq!(escape_pattern("a\\b"), "a\\\\b");
+  }
+}
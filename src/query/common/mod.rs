@@ -1 +1,6 @@
+pub mod export;
+pub mod flight_sql;
+pub mod http_query;
+pub mod metadata;
+pub mod output_mapping;
 pub mod query_processor;
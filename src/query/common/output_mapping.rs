@@ -0,0 +1,220 @@
+use crate::client::options::{ColumnTransform, OutputMapping};
+use crate::error::Result;
+use crate::query::common::query_processor::RecordBatchStream;
+use arrow::array::{ArrayRef, AsArray, GenericStringArray, OffsetSizeTrait, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::error::ArrowError;
+use futures_util::StreamExt;
+use std::sync::Arc;
+
+/// Applies `mapping`, when there is one, to every batch of `response`.
+pub(crate) fn map_output(
+  response: RecordBatchStream,
+  mapping: Option<OutputMapping>,
+) -> RecordBatchStream {
+  match mapping {
+    Some(mapping) => Box::pin(
+      response
+        .map(move |batch| -> Result<RecordBatch> { Ok(apply_output_mapping(batch?, &mapping)?) }),
+    ),
+    None => response,
+  }
+}
+
+/// Keeps the selected columns of `batch`, transforms their values and renames them.
+///
+/// Transforms and renames are looked up by the original column name. Transformed columns lose
+/// their field metadata, e.g. the IOx column type, which describes the original values.
+pub(crate) fn apply_output_mapping(
+  batch: RecordBatch,
+  mapping: &OutputMapping,
+) -> Result<RecordBatch, ArrowError> {
+  let schema = batch.schema();
+  let indices = match &mapping.columns {
+    Some(columns) => columns
+      .iter()
+      .map(|name| schema.index_of(name))
+      .collect::<Result<Vec<_>, _>>()?,
+    None => (0..schema.fields().len()).collect(),
+  };
+
+  let mut fields = Vec::with_capacity(indices.len());
+  let mut columns = Vec::with_capacity(indices.len());
+
+  for index in indices {
+    let field = schema.field(index);
+    let mut column = batch.column(index).clone();
+    let mut data_type = field.data_type().clone();
+    let mut nullable = field.is_nullable();
+    let mut metadata = field.metadata().clone();
+
+    if let Some(transform) = mapping
+      .transforms
+      .as_ref()
+      .and_then(|transforms| transforms.get(field.name()))
+    {
+      column = transform_column(field, column, transform)?;
+      data_type = column.data_type().clone();
+      nullable &= transform.null_as_empty != Some(true);
+      metadata.clear();
+    }
+
+    let name = mapping
+      .rename
+      .as_ref()
+      .and_then(|rename| rename.get(field.name()))
+      .unwrap_or(field.name());
+
+    fields.push(Field::new(name, data_type, nullable).with_metadata(metadata));
+    columns.push(column);
+  }
+
+  let schema = Schema::new(fields).with_metadata(schema.metadata().clone());
+  RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+fn transform_column(
+  field: &Field,
+  mut column: ArrayRef,
+  transform: &ColumnTransform,
+) -> Result<ArrayRef, ArrowError> {
+  if transform.null_as_empty == Some(true) {
+    // Tags arrive dictionary encoded, the filled column is a plain string column
+    column = match column.data_type() {
+      DataType::Utf8 => fill_nulls::<i32>(&column),
+      DataType::LargeUtf8 => fill_nulls::<i64>(&column),
+      DataType::Dictionary(_, values) if values.as_ref() == &DataType::Utf8 => {
+        fill_nulls::<i32>(&cast(&column, &DataType::Utf8)?)
+      }
+      DataType::Dictionary(_, values) if values.as_ref() == &DataType::LargeUtf8 => {
+        fill_nulls::<i64>(&cast(&column, &DataType::LargeUtf8)?)
+      }
+      data_type => {
+        return Err(ArrowError::InvalidArgumentError(format!(
+          "Cannot fill nulls of non-string column '{}' of type {data_type}",
+          field.name(),
+        )))
+      }
+    };
+  }
+
+  if let Some(factor) = transform.scale {
+    if !column.data_type().is_numeric() {
+      return Err(ArrowError::InvalidArgumentError(format!(
+        "Cannot scale non-numeric column '{}' of type {}",
+        field.name(),
+        column.data_type()
+      )));
+    }
+
+    let values = cast(&column, &DataType::Float64)?;
+    column = Arc::new(
+      values
+        .as_primitive::<Float64Type>()
+        .unary::<_, Float64Type>(|value| value * factor),
+    );
+  }
+
+  Ok(column)
+}
+
+fn fill_nulls<O: OffsetSizeTrait>(column: &ArrayRef) -> ArrayRef {
+  Arc::new(
+    column
+      .as_string::<O>()
+      .iter()
+      .map(|value| Some(value.unwrap_or_default()))
+      .collect::<GenericStringArray<O>>(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::array::{DictionaryArray, Float64Array, Int64Array};
+  use arrow::datatypes::Int32Type;
+  use std::collections::HashMap;
+
+  #[test]
+  fn test_output_mapping() {
+    let host: DictionaryArray<Int32Type> = vec![Some("a"), None].into_iter().collect();
+    let schema = Arc::new(Schema::new(vec![
+      Field::new("time", DataType::Int64, false),
+      Field::new("host", host.data_type().clone(), true).with_metadata(HashMap::from([(
+        "iox::column::type".to_string(),
+        "iox::column_type::tag".to_string(),
+      )])),
+      Field::new("usage", DataType::Float64, false),
+    ]));
+    let batch = RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(Int64Array::from(vec![1, 2])),
+        Arc::new(host),
+        Arc::new(Float64Array::from(vec![0.5, 0.25])),
+      ],
+    )
+    .unwrap();
+
+    let mapping = OutputMapping {
+      columns: Some(vec![
+        "usage".to_string(),
+        "host".to_string(),
+        "time".to_string(),
+      ]),
+      rename: Some(HashMap::from([("time".to_string(), "ts".to_string())])),
+      transforms: Some(HashMap::from([
+        (
+          "usage".to_string(),
+          ColumnTransform {
+            scale: Some(100.0),
+            ..Default::default()
+          },
+        ),
+        (
+          "host".to_string(),
+          ColumnTransform {
+            null_as_empty: Some(true),
+            ..Default::default()
+          },
+        ),
+      ])),
+    };
+
+    let mapped = apply_output_mapping(batch, &mapping).unwrap();
+    let names: Vec<_> = mapped
+      .schema()
+      .fields()
+      .iter()
+      .map(|f| f.name().clone())
+      .collect();
+
+    assert_eq!(names, vec!["usage", "host", "ts"]);
+    assert_eq!(
+      mapped.column(0).as_primitive::<Float64Type>().value(0),
+      50.0
+    );
+    assert_eq!(mapped.column(1).as_string::<i32>().value(1), "");
+    assert!(!mapped.schema().field(1).is_nullable());
+    assert!(mapped.schema().field(1).metadata().is_empty());
+
+    let numbers = OutputMapping {
+      transforms: Some(HashMap::from([(
+        "usage".to_string(),
+        ColumnTransform {
+          null_as_empty: Some(true),
+          ..Default::default()
+        },
+      )])),
+      ..Default::default()
+    };
+    assert!(apply_output_mapping(mapped.clone(), &numbers).is_err());
+
+    let unknown = OutputMapping {
+      columns: Some(vec!["missing".to_string()]),
+      ..Default::default()
+    };
+    assert!(apply_output_mapping(mapped, &unknown).is_err());
+  }
+}
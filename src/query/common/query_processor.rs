@@ -1,28 +1,457 @@
-use crate::serializer::common::SerializerTrait;
+use crate::error::{Error, Result};
+use crate::serializer::common::columnar_serializer::{ColumnarBatch, ColumnarSerializer};
+use crate::serializer::common::library_serializer::{LibraryReturnType, LibrarySerializer};
+use crate::serializer::common::raw_serializer::{
+  serialize_file, serialize_table, IpcStreamEncoder, RawSerializer,
+};
+use crate::serializer::common::text_serializer::{concat_chunks, CsvSerializer, NdjsonSerializer};
+use crate::serializer::common::unsafe_serializer::{JsonRow, UnsafeSerializer};
+use crate::serializer::common::{
+  BatchSerializer, Chunk, SerializeOptions, Serializer, SerializerTrait, Typed,
+};
 
-use arrow_flight::decode::FlightRecordBatchStream;
-use napi::bindgen_prelude::*;
-use napi::tokio_stream::wrappers::ReceiverStream;
-use tonic::codegen::tokio_stream::StreamExt;
+use arrow::array::RecordBatch;
+use futures_util::stream::BoxStream;
+use futures_util::{Stream, StreamExt};
+use std::pin::pin;
+use std::sync::Arc;
+use tokio_stream::wrappers::ReceiverStream;
 
-pub(crate) fn into_stream<S: SerializerTrait>(
-  mut response: FlightRecordBatchStream,
-) -> ReceiverStream<Result<<S as SerializerTrait>::Output>> {
-  let (tx, rx) = tokio::sync::mpsc::channel::<Result<S::Output>>(100);
+/// Record batches of a query, whichever transport produced them.
+pub(crate) type RecordBatchStream = BoxStream<'static, Result<RecordBatch>>;
+
+/// Stream of a query, one variant per output type of the serializers.
+pub enum QueryStream {
+  Library(ReceiverStream<Result<LibraryReturnType>>),
+  Unsafe(ReceiverStream<Result<JsonRow>>),
+  /// Output of the `Raw`, `RawStream`, `RawFile`, `Csv` and `Ndjson` serializers.
+  Bytes(ReceiverStream<Result<Chunk>>),
+  Columnar(ReceiverStream<Result<ColumnarBatch>>),
+}
+
+/// Rows of a whole query, one variant per output type of the serializers.
+pub enum QueryRows {
+  Library(Vec<LibraryReturnType>),
+  Unsafe(Vec<JsonRow>),
+  /// A single Arrow IPC buffer or the concatenated text of the `Csv` and `Ndjson` serializers.
+  Bytes(Chunk),
+  Columnar(Vec<ColumnarBatch>),
+}
+
+/// Serializes `response` with `serializer`, for every client and prepared statement.
+pub(crate) fn serialize_stream<R>(
+  response: R,
+  serializer: Serializer,
+  options: SerializeOptions,
+) -> QueryStream
+where
+  R: Stream<Item = Result<RecordBatch>> + Send + Unpin + 'static,
+{
+  match serializer {
+    Serializer::Library => {
+      QueryStream::Library(into_stream::<LibrarySerializer, _>(response, options))
+    }
+    Serializer::Unsafe => {
+      QueryStream::Unsafe(into_stream::<UnsafeSerializer, _>(response, options))
+    }
+    Serializer::Raw => QueryStream::Bytes(into_stream::<RawSerializer, _>(response, options)),
+    Serializer::Columnar => {
+      QueryStream::Columnar(into_stream::<ColumnarSerializer, _>(response, options))
+    }
+    Serializer::RawStream => QueryStream::Bytes(into_ipc_stream(response, IpcFormat::Stream)),
+    Serializer::RawFile => QueryStream::Bytes(into_ipc_stream(response, IpcFormat::File)),
+    Serializer::Csv => QueryStream::Bytes(into_stream::<CsvSerializer, _>(response, options)),
+    Serializer::Ndjson => QueryStream::Bytes(into_stream::<NdjsonSerializer, _>(response, options)),
+  }
+}
+
+/// Serializes all `batches` of a query with `serializer`. The `Raw` and `RawStream` serializers
+/// return a single IPC stream, `RawFile` an IPC file and the text serializers their whole text.
+pub(crate) async fn serialize_rows(
+  batches: Vec<RecordBatch>,
+  serializer: Serializer,
+  options: &SerializeOptions,
+) -> Result<QueryRows> {
+  Ok(match serializer {
+    Serializer::Library => {
+      QueryRows::Library(serialize_all::<LibrarySerializer>(batches, options).await?)
+    }
+    Serializer::Unsafe => {
+      QueryRows::Unsafe(serialize_all::<UnsafeSerializer>(batches, options).await?)
+    }
+    Serializer::Raw | Serializer::RawStream => QueryRows::Bytes(serialize_table(&batches)?.into()),
+    Serializer::RawFile => QueryRows::Bytes(serialize_file(&batches)?.into()),
+    Serializer::Csv => QueryRows::Bytes(concat_chunks(
+      serialize_all::<CsvSerializer>(batches, options).await?,
+    )),
+    Serializer::Ndjson => QueryRows::Bytes(concat_chunks(
+      serialize_all::<NdjsonSerializer>(batches, options).await?,
+    )),
+    Serializer::Columnar => {
+      QueryRows::Columnar(serialize_all::<ColumnarSerializer>(batches, options).await?)
+    }
+  })
+}
+
+pub(crate) fn into_stream<S, R>(
+  response: R,
+  options: SerializeOptions,
+) -> ReceiverStream<Result<<S as SerializerTrait>::Output>>
+where
+  S: SerializerTrait + 'static,
+  R: Stream<Item = Result<RecordBatch>> + Send + Unpin + 'static,
+{
+  let serializer: Arc<dyn BatchSerializer<Output = S::Output>> = Arc::new(Typed::<S>::default());
+  into_serializer_stream(response, serializer, options)
+}
+
+/// Like [`into_stream`], with a serializer resolved at runtime.
+///
+/// The first failed batch is sent as an error and ends the stream.
+pub(crate) fn into_serializer_stream<T, R>(
+  response: R,
+  serializer: Arc<dyn BatchSerializer<Output = T>>,
+  options: SerializeOptions,
+) -> ReceiverStream<Result<T>>
+where
+  T: Send + 'static,
+  R: Stream<Item = Result<RecordBatch>> + Send + Unpin + 'static,
+{
+  let (tx, rx) = tokio::sync::mpsc::channel::<Result<T>>(100);
+
+  tokio::spawn(async move {
+    let mut serialized = pin!(serialize_pipelined(response, serializer, options));
+
+    while let Some(items) = serialized.next().await {
+      let items = match items {
+        Ok(items) => items,
+        Err(e) => {
+          let _ = tx.send(Err(e)).await;
+          return;
+        }
+      };
+
+      for item in items {
+        if tx.send(Ok(item)).await.is_err() {
+          return;
+        }
+      }
+    }
+  });
+
+  ReceiverStream::new(rx)
+}
+
+/// Serializes the batches of `response` on the runtime workers, in their original order.
+///
+/// Up to [`pipeline_depth`] batches are serialized at once, so the next batch is fetched while
+/// the previous ones are still being converted. The header of the serializer is yielded together
+/// with the output of the first batch. A failed batch, or a serializer that fails or panics on
+/// one, yields an error.
+fn serialize_pipelined<T, R>(
+  response: R,
+  serializer: Arc<dyn BatchSerializer<Output = T>>,
+  options: SerializeOptions,
+) -> impl Stream<Item = Result<Vec<T>>> + Send
+where
+  T: Send + 'static,
+  R: Stream<Item = Result<RecordBatch>> + Send + 'static,
+{
+  let mut started = false;
+
+  response
+    .map(move |batch| {
+      let mut items = Vec::new();
+      if let (false, Ok(first)) = (started, &batch) {
+        started = true;
+        items.extend(serializer.header(&first.schema()));
+      }
+
+      let handle =
+        batch.map(|batch| tokio::spawn(serializer.serialize(Ok(batch), options.clone())));
+
+      async move {
+        match handle?.await {
+          Ok(Some(data)) => {
+            items.extend(data);
+            Ok(items)
+          }
+          Ok(None) => Err(Error::Request(
+            "Failed to serialize a record batch".to_string(),
+          )),
+          Err(e) => Err(Error::Request(format!("Serializer task failed: {e}"))),
+        }
+      }
+    })
+    .buffered(pipeline_depth())
+}
+
+/// Number of batches serialized concurrently by a query.
+fn pipeline_depth() -> usize {
+  std::thread::available_parallelism()
+    .map(|parallelism| parallelism.get())
+    .unwrap_or(1)
+    .clamp(2, 16)
+}
+
+/// Decodes every row of `response` into `T` with `serde_arrow`, for the Rust API.
+///
+/// The first failed batch or row that does not match `T` ends the stream with an error.
+pub(crate) fn into_typed_stream<T, R>(mut response: R) -> ReceiverStream<Result<T>>
+where
+  T: serde::de::DeserializeOwned + Send + 'static,
+  R: Stream<Item = Result<RecordBatch>> + Send + Unpin + 'static,
+{
+  let (tx, rx) = tokio::sync::mpsc::channel::<Result<T>>(100);
 
   tokio::spawn(async move {
     while let Some(batch) = response.next().await {
-      let serialized_result = S::serialize(batch).await;
+      let rows = batch.and_then(|batch| {
+        serde_arrow::from_record_batch::<Vec<T>>(&batch)
+          .map_err(|e| Error::Request(format!("Failed to deserialize rows: {e}")))
+      });
 
-      if let Some(data) = serialized_result {
-        for item in data {
-          if tx.send(Ok(item)).await.is_err() {
-            break;
+      match rows {
+        Ok(rows) => {
+          for row in rows {
+            if tx.send(Ok(row)).await.is_err() {
+              return;
+            }
           }
         }
+        Err(e) => {
+          let _ = tx.send(Err(e)).await;
+          return;
+        }
+      }
+    }
+  });
+
+  ReceiverStream::new(rx)
+}
+
+/// Layout of the Arrow IPC output of the `raw_stream` and `raw_file` serializers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IpcFormat {
+  /// One continuous IPC stream, a chunk per record batch.
+  Stream,
+  /// One IPC file in a single buffer once the query completed.
+  File,
+}
+
+pub(crate) fn into_ipc_stream<R>(
+  mut response: R,
+  format: IpcFormat,
+) -> ReceiverStream<Result<Chunk>>
+where
+  R: Stream<Item = Result<RecordBatch>> + Send + Unpin + 'static,
+{
+  let (tx, rx) = tokio::sync::mpsc::channel::<Result<Chunk>>(100);
+
+  tokio::spawn(async move {
+    let mut encoder = IpcStreamEncoder::default();
+    let mut batches = Vec::new();
+
+    while let Some(batch) = response.next().await {
+      let batch = match batch {
+        Ok(batch) => batch,
+        Err(e) => {
+          let _ = tx.send(Err(e)).await;
+          return;
+        }
+      };
+
+      if format == IpcFormat::File {
+        batches.push(batch);
+        continue;
+      }
+
+      let chunk = encoder.write(&batch).map_err(Error::from);
+      if tx.send(chunk.map(Chunk::from)).await.is_err() {
+        return;
+      }
+    }
+
+    let last = match format {
+      IpcFormat::Stream => encoder.finish(),
+      IpcFormat::File => serialize_file(&batches),
+    };
+
+    match last {
+      Ok(bytes) if bytes.is_empty() => {}
+      Ok(bytes) => {
+        let _ = tx.send(Ok(bytes.into())).await;
+      }
+      Err(e) => {
+        let _ = tx.send(Err(e.into())).await;
       }
     }
   });
 
   ReceiverStream::new(rx)
 }
+
+/// Drains all batches of a query, failing as soon as more than `max_rows` rows were received.
+pub(crate) async fn collect_batches(
+  mut response: RecordBatchStream,
+  max_rows: u32,
+) -> Result<Vec<RecordBatch>> {
+  let mut batches = Vec::new();
+  let mut row_count = 0;
+
+  while let Some(batch) = response.next().await {
+    let batch = batch?;
+
+    row_count += batch.num_rows();
+    if row_count > max_rows as usize {
+      return Err(Error::Request(format!(
+        "Query returned more than {max_rows} rows, raise maxRows or narrow the query"
+      )));
+    }
+
+    batches.push(batch);
+  }
+
+  Ok(batches)
+}
+
+/// Serializes all `batches` with `S`, failing when any of them can't be serialized.
+async fn serialize_all<S: SerializerTrait + 'static>(
+  batches: Vec<RecordBatch>,
+  options: &SerializeOptions,
+) -> Result<Vec<S::Output>> {
+  let response = futures_util::stream::iter(batches.into_iter().map(Ok));
+  let serializer: Arc<dyn BatchSerializer<Output = S::Output>> = Arc::new(Typed::<S>::default());
+  let mut serialized = pin!(serialize_pipelined(response, serializer, options.clone()));
+  let mut rows = Vec::new();
+
+  while let Some(items) = serialized.next().await {
+    rows.extend(items?);
+  }
+
+  Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::array::{AsArray, Int64Array};
+  use arrow::datatypes::{DataType, Field, Int64Type, Schema, SchemaRef};
+
+  struct FirstValueSerializer;
+  impl SerializerTrait for FirstValueSerializer {
+    type Output = i64;
+
+    async fn serialize(
+      batch: Result<RecordBatch>,
+      _options: &SerializeOptions,
+    ) -> Option<Vec<Self::Output>> {
+      // Later batches finish first, the output must still follow the batch order
+      let batch = batch.ok()?;
+      let value = batch.column(0).as_primitive::<Int64Type>().value(0);
+      if value < 0 {
+        return None;
+      }
+      for _ in 0..(100 - value) {
+        tokio::task::yield_now().await;
+      }
+      Some(vec![value])
+    }
+
+    fn header(_schema: &SchemaRef) -> Option<Self::Output> {
+      Some(-1)
+    }
+  }
+
+  #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+  async fn test_pipeline_preserves_order() {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+      "value",
+      DataType::Int64,
+      false,
+    )]));
+    let batches = (0..20)
+      .map(|i| {
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(vec![i]))]).unwrap()
+      })
+      .collect();
+
+    let rows = serialize_all::<FirstValueSerializer>(batches, &SerializeOptions::default())
+      .await
+      .unwrap();
+
+    let mut expected = vec![-1];
+    expected.extend(0..20);
+    assert_eq!(rows, expected);
+  }
+
+  #[tokio::test]
+  async fn test_serialize_all_fails_on_batch() {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+      "value",
+      DataType::Int64,
+      false,
+    )]));
+    let batches = [1, -1, 2]
+      .into_iter()
+      .map(|i| {
+        RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(vec![i]))]).unwrap()
+      })
+      .collect();
+
+    let rows = serialize_all::<FirstValueSerializer>(batches, &SerializeOptions::default()).await;
+
+    assert!(rows.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_stream_sends_failed_batch() {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+      "value",
+      DataType::Int64,
+      false,
+    )]));
+    let batch =
+      |i| RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(vec![i]))]).unwrap();
+    let response = futures_util::stream::iter(vec![
+      Ok(batch(1)),
+      Err(Error::Request("connection reset".to_string())),
+      Ok(batch(2)),
+    ]);
+
+    let items: Vec<Result<i64>> =
+      into_stream::<FirstValueSerializer, _>(response, SerializeOptions::default())
+        .collect()
+        .await;
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].as_ref().unwrap(), &-1);
+    assert_eq!(items[1].as_ref().unwrap(), &1);
+    assert_eq!(
+      items[2].as_ref().unwrap_err(),
+      &Error::Request("connection reset".to_string())
+    );
+  }
+
+  #[tokio::test]
+  async fn test_typed_rows() {
+    #[derive(serde::Deserialize)]
+    struct Row {
+      value: i64,
+    }
+
+    let schema = Arc::new(Schema::new(vec![Field::new(
+      "value",
+      DataType::Int64,
+      false,
+    )]));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(vec![1, 2]))]).unwrap();
+
+    let rows: Vec<Row> = into_typed_stream(futures_util::stream::iter(vec![Ok(batch)]))
+      .map(|row| row.unwrap())
+      .collect()
+      .await;
+
+    assert_eq!(rows.iter().map(|row| row.value).sum::<i64>(), 3);
+  }
+}
@@ -1,5 +1,2 @@
-#[cfg(target_arch = "wasm32")]
-pub mod browser;
-
 #[cfg(not(target_arch = "wasm32"))]
 pub mod common;
@@ -1,8 +1,6 @@
-#[cfg(target_arch = "wasm32")]
-pub(crate) mod unsafe_serializer;
-
 #[napi_derive::napi]
 pub enum Serializer {
+  /// Rows as plain objects with the JSON values of the HTTP query API.
   Unsafe,
 }
 impl Default for Serializer {
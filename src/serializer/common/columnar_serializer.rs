@@ -0,0 +1,341 @@
+use crate::error::Result;
+use crate::serializer::common::{SerializeOptions, SerializerTrait};
+use arrow::array::{Array, ArrayRef, AsArray, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::{
+  DataType, Date32Type, Date64Type, DurationMicrosecondType, DurationMillisecondType,
+  DurationNanosecondType, DurationSecondType, Float16Type, Float32Type, Float64Type, Int16Type,
+  Int32Type, Int64Type, Int8Type, Time32MillisecondType, Time32SecondType, Time64MicrosecondType,
+  Time64NanosecondType, TimeUnit, TimestampMicrosecondType, TimestampMillisecondType,
+  TimestampNanosecondType, TimestampSecondType, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+#[cfg(feature = "napi")]
+use napi::bindgen_prelude::{
+  BigInt64Array, BigUint64Array, Float32Array, Float64Array, Int16Array, Int32Array, Int8Array,
+  ToNapiValue, Uint16Array, Uint32Array, Uint8Array,
+};
+#[cfg(feature = "napi")]
+use napi::check_status;
+#[cfg(feature = "napi")]
+use napi::sys::{napi_env, napi_value};
+
+pub struct ColumnarSerializer;
+
+/// One record batch as columns instead of rows.
+///
+/// In JS this is `{ numRows, columns, validity }`: `columns` maps every column name to a typed
+/// array (or an array of strings/booleans) in schema order, `validity` holds the Arrow null
+/// bitmap (LSB first) of the columns that contain nulls.
+#[derive(Debug, Clone)]
+pub struct ColumnarBatch {
+  pub num_rows: u32,
+  pub columns: Vec<(String, ColumnValues)>,
+  pub validity: Vec<(String, Vec<u8>)>,
+}
+
+/// Values of a single column. Timestamps are epoch nanoseconds, dates, times and durations
+/// keep the raw value of their Arrow unit.
+#[derive(Debug, Clone)]
+pub enum ColumnValues {
+  Int8(Vec<i8>),
+  Int16(Vec<i16>),
+  Int32(Vec<i32>),
+  Int64(Vec<i64>),
+  UInt8(Vec<u8>),
+  UInt16(Vec<u16>),
+  UInt32(Vec<u32>),
+  UInt64(Vec<u64>),
+  Float32(Vec<f32>),
+  Float64(Vec<f64>),
+  Bool(Vec<Option<bool>>),
+  Text(Vec<Option<String>>),
+}
+
+impl SerializerTrait for ColumnarSerializer {
+  type Output = ColumnarBatch;
+
+  async fn serialize(
+    batch: Result<RecordBatch>,
+    _options: &SerializeOptions,
+  ) -> Option<Vec<Self::Output>> {
+    if let Ok(batch) = batch {
+      let schema = batch.schema();
+
+      let mut columns = Vec::with_capacity(batch.num_columns());
+      let mut validity = Vec::new();
+
+      for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        if let Some(nulls) = column.logical_nulls() {
+          if nulls.null_count() > 0 {
+            validity.push((field.name().clone(), nulls.inner().sliced().to_vec()));
+          }
+        }
+
+        columns.push((field.name().clone(), Self::serialize_column(column)));
+      }
+
+      Some(vec![ColumnarBatch {
+        num_rows: batch.num_rows() as u32,
+        columns,
+        validity,
+      }])
+    } else {
+      None
+    }
+  }
+}
+
+impl ColumnarSerializer {
+  fn serialize_column(column: &ArrayRef) -> ColumnValues {
+    match column.data_type() {
+      DataType::Int8 => ColumnValues::Int8(column.as_primitive::<Int8Type>().values().to_vec()),
+      DataType::Int16 => ColumnValues::Int16(column.as_primitive::<Int16Type>().values().to_vec()),
+      DataType::Int32 => ColumnValues::Int32(column.as_primitive::<Int32Type>().values().to_vec()),
+      DataType::Int64 => ColumnValues::Int64(column.as_primitive::<Int64Type>().values().to_vec()),
+      DataType::UInt8 => ColumnValues::UInt8(column.as_primitive::<UInt8Type>().values().to_vec()),
+      DataType::UInt16 => {
+        ColumnValues::UInt16(column.as_primitive::<UInt16Type>().values().to_vec())
+      }
+      DataType::UInt32 => {
+        ColumnValues::UInt32(column.as_primitive::<UInt32Type>().values().to_vec())
+      }
+      DataType::UInt64 => {
+        ColumnValues::UInt64(column.as_primitive::<UInt64Type>().values().to_vec())
+      }
+      DataType::Float16 => ColumnValues::Float32(
+        column
+          .as_primitive::<Float16Type>()
+          .values()
+          .iter()
+          .map(|value| value.to_f32())
+          .collect(),
+      ),
+      DataType::Float32 => {
+        ColumnValues::Float32(column.as_primitive::<Float32Type>().values().to_vec())
+      }
+      DataType::Float64 => {
+        ColumnValues::Float64(column.as_primitive::<Float64Type>().values().to_vec())
+      }
+      DataType::Date32 => {
+        ColumnValues::Int32(column.as_primitive::<Date32Type>().values().to_vec())
+      }
+      DataType::Date64 => {
+        ColumnValues::Int64(column.as_primitive::<Date64Type>().values().to_vec())
+      }
+      DataType::Time32(TimeUnit::Second) => {
+        ColumnValues::Int32(column.as_primitive::<Time32SecondType>().values().to_vec())
+      }
+      DataType::Time32(_) => ColumnValues::Int32(
+        column
+          .as_primitive::<Time32MillisecondType>()
+          .values()
+          .to_vec(),
+      ),
+      DataType::Time64(TimeUnit::Microsecond) => ColumnValues::Int64(
+        column
+          .as_primitive::<Time64MicrosecondType>()
+          .values()
+          .to_vec(),
+      ),
+      DataType::Time64(_) => ColumnValues::Int64(
+        column
+          .as_primitive::<Time64NanosecondType>()
+          .values()
+          .to_vec(),
+      ),
+      DataType::Duration(time_unit) => ColumnValues::Int64(match time_unit {
+        TimeUnit::Second => column
+          .as_primitive::<DurationSecondType>()
+          .values()
+          .to_vec(),
+        TimeUnit::Millisecond => column
+          .as_primitive::<DurationMillisecondType>()
+          .values()
+          .to_vec(),
+        TimeUnit::Microsecond => column
+          .as_primitive::<DurationMicrosecondType>()
+          .values()
+          .to_vec(),
+        TimeUnit::Nanosecond => column
+          .as_primitive::<DurationNanosecondType>()
+          .values()
+          .to_vec(),
+      }),
+      DataType::Timestamp(time_unit, _) => {
+        let (values, factor) = match time_unit {
+          TimeUnit::Second => (
+            column.as_primitive::<TimestampSecondType>().values(),
+            1_000_000_000,
+          ),
+          TimeUnit::Millisecond => (
+            column.as_primitive::<TimestampMillisecondType>().values(),
+            1_000_000,
+          ),
+          TimeUnit::Microsecond => (
+            column.as_primitive::<TimestampMicrosecondType>().values(),
+            1_000,
+          ),
+          TimeUnit::Nanosecond => (column.as_primitive::<TimestampNanosecondType>().values(), 1),
+        };
+        ColumnValues::Int64(
+          values
+            .iter()
+            .map(|value| value.saturating_mul(factor))
+            .collect(),
+        )
+      }
+      DataType::Boolean => ColumnValues::Bool(column.as_boolean().iter().collect()),
+      DataType::Utf8 => ColumnValues::Text(
+        column
+          .as_string::<i32>()
+          .iter()
+          .map(|value| value.map(String::from))
+          .collect(),
+      ),
+      DataType::LargeUtf8 | DataType::Utf8View | DataType::Dictionary(_, _) => {
+        match cast(column, &DataType::Utf8) {
+          Ok(strings) => Self::serialize_column(&strings),
+          Err(_) => Self::format_column(column),
+        }
+      }
+      _ => Self::format_column(column),
+    }
+  }
+
+  /// Renders types without a typed array counterpart with Arrow's display formatting.
+  fn format_column(column: &ArrayRef) -> ColumnValues {
+    let options = FormatOptions::default();
+    let Ok(formatter) = ArrayFormatter::try_new(column.as_ref(), &options) else {
+      return ColumnValues::Text(vec![None; column.len()]);
+    };
+
+    ColumnValues::Text(
+      (0..column.len())
+        .map(|i| {
+          if column.is_null(i) {
+            None
+          } else {
+            Some(formatter.value(i).to_string())
+          }
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(feature = "napi")]
+impl ToNapiValue for ColumnValues {
+  unsafe fn to_napi_value(env: napi_env, val: Self) -> napi::Result<napi_value> {
+    match val {
+      ColumnValues::Int8(v) => ToNapiValue::to_napi_value(env, Int8Array::new(v)),
+      ColumnValues::Int16(v) => ToNapiValue::to_napi_value(env, Int16Array::new(v)),
+      ColumnValues::Int32(v) => ToNapiValue::to_napi_value(env, Int32Array::new(v)),
+      ColumnValues::Int64(v) => ToNapiValue::to_napi_value(env, BigInt64Array::new(v)),
+      ColumnValues::UInt8(v) => ToNapiValue::to_napi_value(env, Uint8Array::new(v)),
+      ColumnValues::UInt16(v) => ToNapiValue::to_napi_value(env, Uint16Array::new(v)),
+      ColumnValues::UInt32(v) => ToNapiValue::to_napi_value(env, Uint32Array::new(v)),
+      ColumnValues::UInt64(v) => ToNapiValue::to_napi_value(env, BigUint64Array::new(v)),
+      ColumnValues::Float32(v) => ToNapiValue::to_napi_value(env, Float32Array::new(v)),
+      ColumnValues::Float64(v) => ToNapiValue::to_napi_value(env, Float64Array::new(v)),
+      ColumnValues::Bool(v) => ToNapiValue::to_napi_value(env, v),
+      ColumnValues::Text(v) => ToNapiValue::to_napi_value(env, v),
+    }
+  }
+}
+
+#[cfg(feature = "napi")]
+impl ToNapiValue for ColumnarBatch {
+  unsafe fn to_napi_value(env: napi_env, val: Self) -> napi::Result<napi_value> {
+    let columns = create_object(env)?;
+    for (name, values) in val.columns {
+      set_property(env, columns, name, ToNapiValue::to_napi_value(env, values)?)?;
+    }
+
+    let validity = create_object(env)?;
+    for (name, bitmap) in val.validity {
+      set_property(
+        env,
+        validity,
+        name,
+        ToNapiValue::to_napi_value(env, Uint8Array::new(bitmap))?,
+      )?;
+    }
+
+    let batch = create_object(env)?;
+    set_property(
+      env,
+      batch,
+      String::from("numRows"),
+      ToNapiValue::to_napi_value(env, val.num_rows)?,
+    )?;
+    set_property(env, batch, String::from("columns"), columns)?;
+    set_property(env, batch, String::from("validity"), validity)?;
+
+    Ok(batch)
+  }
+}
+
+#[cfg(feature = "napi")]
+pub(crate) unsafe fn create_object(env: napi_env) -> napi::Result<napi_value> {
+  let mut object = std::ptr::null_mut();
+  check_status!(napi::sys::napi_create_object(env, &mut object))?;
+  Ok(object)
+}
+
+#[cfg(feature = "napi")]
+pub(crate) unsafe fn set_property(
+  env: napi_env,
+  object: napi_value,
+  key: String,
+  value: napi_value,
+) -> napi::Result<()> {
+  let key = ToNapiValue::to_napi_value(env, key)?;
+  check_status!(napi::sys::napi_set_property(env, object, key, value))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::array::{Float64Array as ArrowFloat64Array, StringArray, TimestampMillisecondArray};
+  use arrow::datatypes::{Field, Schema};
+  use std::sync::Arc;
+
+  #[tokio::test]
+  async fn test_columnar_batch() {
+    let schema = Arc::new(Schema::new(vec![
+      Field::new("usage", DataType::Float64, true),
+      Field::new("host", DataType::Utf8, true),
+      Field::new(
+        "time",
+        DataType::Timestamp(TimeUnit::Millisecond, None),
+        false,
+      ),
+    ]));
+    let batch = RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(ArrowFloat64Array::from(vec![Some(0.5), None])),
+        Arc::new(StringArray::from(vec![Some("a"), Some("b")])),
+        Arc::new(TimestampMillisecondArray::from(vec![1, 2])),
+      ],
+    )
+    .unwrap();
+
+    let batches = ColumnarSerializer::serialize(Ok(batch), &SerializeOptions::default())
+      .await
+      .unwrap();
+    let ColumnarBatch {
+      num_rows,
+      columns,
+      validity,
+    } = &batches[0];
+
+    assert_eq!(*num_rows, 2);
+    assert!(matches!(&columns[0].1, ColumnValues::Float64(v) if v[0] == 0.5));
+    assert!(matches!(&columns[2].1, ColumnValues::Int64(v) if v == &vec![1_000_000, 2_000_000]));
+    assert_eq!(validity.len(), 1);
+    assert_eq!(validity[0].0, "usage");
+    assert_eq!(validity[0].1[0] & 0b11, 0b01);
+  }
+}
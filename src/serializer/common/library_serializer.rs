@@ -1,85 +1,195 @@
-use crate::serializer::common::SerializerTrait;
-use crate::Value;
+use crate::client::options::{Int64Mode, NullMode};
+use crate::error::Result;
+use crate::serializer::common::temporal::{
+  duration_value, interval_value, parse_timezone, time_value, timestamp_value,
+};
+use crate::serializer::common::{SerializeOptions, SerializerTrait};
+use crate::serializer::shared_strings::SharedStrings;
+use crate::{DictionaryValue, ReturnDataType, Value};
 use arrow::array::{
-  Array,
-  ArrayData,
-  ArrayRef,
-  BooleanArray,
-  Date32Array,
-  Date64Array,
-  DurationMicrosecondArray,
-  DurationMillisecondArray,
-  DurationNanosecondArray,
-  DurationSecondArray,
-  FixedSizeListArray,
-  Float16Array,
-  Float32Array,
-  Float64Array,
-  Int16Array,
-  Int32Array,
-  Int64Array,
-  Int8Array,
-  // LargeListArray,
-  ListArray,
-  RecordBatch,
-  StringArray,
-  StringViewArray,
-  Time32MillisecondArray,
-  Time32SecondArray,
-  Time64MicrosecondArray,
-  Time64NanosecondArray,
-  TimestampMicrosecondArray,
-  TimestampMillisecondArray,
-  TimestampNanosecondArray,
-  TimestampSecondArray,
-  UInt16Array,
-  UInt32Array,
-  UInt64Array,
-  UInt8Array,
+  Array, ArrayData, ArrayRef, AsArray, BinaryArray, BinaryViewArray, BooleanArray, Date32Array,
+  Date64Array, Decimal128Array, Decimal256Array, DurationMicrosecondArray,
+  DurationMillisecondArray, DurationNanosecondArray, DurationSecondArray, FixedSizeBinaryArray,
+  FixedSizeListArray, Float16Array, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
+  Int8Array, LargeBinaryArray, LargeListArray, LargeStringArray, ListArray, MapArray, RecordBatch,
+  StringArray, StringViewArray, StructArray, Time32MillisecondArray, Time32SecondArray,
+  Time64MicrosecondArray, Time64NanosecondArray, TimestampMicrosecondArray,
+  TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray, UInt16Array,
+  UInt32Array, UInt64Array, UInt8Array,
+};
+use arrow::compute::cast;
+use arrow::datatypes::{
+  DataType, Int64Type, IntervalDayTimeType, IntervalMonthDayNanoType, IntervalUnit,
+  IntervalYearMonthType, SchemaRef, TimeUnit,
 };
-use arrow::datatypes::{DataType, TimeUnit};
-use arrow_flight::error::Result as FlightResult;
-use napi_derive::napi;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+#[cfg(feature = "napi")]
+use napi::bindgen_prelude::{Null, ToNapiValue};
+#[cfg(feature = "napi")]
+use napi::check_status;
+#[cfg(feature = "napi")]
+use napi::sys::{napi_env, napi_property_descriptor, napi_value, PropertyAttributes};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct LibrarySerializer;
 
-#[napi]
-pub type LibraryReturnType = HashMap<String, Option<Value>>;
+pub type LibraryReturnType = LibraryRow;
+
+/// One row of the `library` serializer.
+///
+/// Values follow the column order of the batch and the column names are shared by all of its
+/// rows, so no map is built per row and every row object is created with the same property
+/// layout, letting V8 share one hidden class between them. [`LibraryRow::get`] looks columns up
+/// by name and [`LibraryRow::into_map`] converts the row into the map rows used to be.
+#[derive(Debug, Clone)]
+pub struct LibraryRow {
+  layout: Arc<RowLayout>,
+  values: Vec<Option<Value>>,
+  null_mode: NullMode,
+}
+
+/// Column names of a batch and their position, shared by all of its rows, which can be dropped
+/// on any thread.
+#[derive(Debug)]
+struct RowLayout {
+  keys: SharedStrings,
+  index: HashMap<String, usize>,
+}
+
+impl RowLayout {
+  fn new(schema: &SchemaRef) -> Self {
+    let names = schema.fields().iter().map(|field| field.name().as_str());
+
+    Self {
+      keys: SharedStrings::new(names.clone()),
+      index: names
+        .enumerate()
+        .map(|(index, name)| (name.to_string(), index))
+        .collect(),
+    }
+  }
+}
+
+impl LibraryRow {
+  /// Value of `column`, `None` when the column is missing or the value is null.
+  pub fn get(&self, column: &str) -> Option<&Value> {
+    let index = *self.layout.index.get(column)?;
+    self.values[index].as_ref()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&Value>)> {
+    self
+      .layout
+      .keys
+      .iter()
+      .zip(self.values.iter().map(Option::as_ref))
+  }
+
+  /// Column names mapped to their values.
+  pub fn into_map(self) -> ReturnDataType {
+    self
+      .layout
+      .keys
+      .iter()
+      .map(str::to_string)
+      .zip(self.values)
+      .collect()
+  }
+}
+
+impl From<LibraryRow> for ReturnDataType {
+  fn from(row: LibraryRow) -> Self {
+    row.into_map()
+  }
+}
+
+#[cfg(feature = "napi")]
+impl ToNapiValue for LibraryRow {
+  unsafe fn to_napi_value(env: napi_env, val: Self) -> napi::Result<napi_value> {
+    let mut object = std::ptr::null_mut();
+    check_status!(napi::sys::napi_create_object(env, &mut object))?;
+
+    let mut properties = Vec::with_capacity(val.values.len());
+    for (index, value) in val.values.into_iter().enumerate() {
+      let value = match (value, val.null_mode) {
+        (Some(value), _) => ToNapiValue::to_napi_value(env, value)?,
+        (None, NullMode::Omit) => continue,
+        (None, NullMode::Null) => ToNapiValue::to_napi_value(env, Null)?,
+        (None, NullMode::Undefined) => ToNapiValue::to_napi_value(env, ())?,
+      };
+
+      properties.push(napi_property_descriptor {
+        utf8name: std::ptr::null(),
+        // Key strings are created once per batch instead of once per row
+        name: val.layout.keys.to_napi_value(env, index)?,
+        method: None,
+        getter: None,
+        setter: None,
+        value,
+        attributes: PropertyAttributes::writable
+          | PropertyAttributes::enumerable
+          | PropertyAttributes::configurable,
+        data: std::ptr::null_mut(),
+      });
+    }
+
+    // A single call defines all properties in schema order
+    check_status!(napi::sys::napi_define_properties(
+      env,
+      object,
+      properties.len(),
+      properties.as_ptr()
+    ))?;
+
+    Ok(object)
+  }
+}
+
 impl SerializerTrait for LibrarySerializer {
   type Output = LibraryReturnType;
 
-  async fn serialize(batch: FlightResult<RecordBatch>) -> Option<Vec<Self::Output>> {
+  async fn serialize(
+    batch: Result<RecordBatch>,
+    options: &SerializeOptions,
+  ) -> Option<Vec<Self::Output>> {
     if let Ok(batch) = batch {
       let schema = batch.schema();
       let row_count = batch.num_rows();
       let field_count = schema.fields().len();
 
-      let mut rows: Vec<Self::Output> = (0..row_count)
-        .map(|_| HashMap::with_capacity(field_count))
-        .collect();
+      let layout = Arc::new(RowLayout::new(&schema));
 
       let mut handles = Vec::with_capacity(field_count);
 
-      for (col_index, field) in schema.fields().iter().enumerate() {
+      for col_index in 0..field_count {
         let column = batch.column(col_index).clone();
-        let field_name = field.name().clone();
+        let options = options.clone();
 
-        let handle = napi::tokio::task::spawn_blocking(move || {
-          Self::serialize_column(&column, field_name, row_count)
-        });
+        let handle =
+          tokio::task::spawn_blocking(move || Self::serialize_column(&column, row_count, &options));
 
         handles.push(handle);
       }
 
+      let mut columns = Vec::with_capacity(field_count);
       for handle in handles {
-        let (col_name, column_values) = handle.await.unwrap();
-        for (row_idx, value) in column_values.into_iter().enumerate() {
-          rows[row_idx].insert(col_name.clone(), value);
-        }
+        let column_values = handle.await.unwrap();
+        columns.push(column_values.into_iter());
       }
 
+      let null_mode = options.null_mode.unwrap_or(NullMode::Null);
+      let rows = (0..row_count)
+        .map(|_| LibraryRow {
+          layout: layout.clone(),
+          null_mode,
+          values: columns
+            .iter_mut()
+            .map(|values| values.next().flatten())
+            .collect(),
+        })
+        .collect();
+
       Some(rows)
     } else {
       None
@@ -90,9 +200,27 @@ impl SerializerTrait for LibrarySerializer {
 impl LibrarySerializer {
   fn serialize_column(
     column: &Arc<dyn Array>,
-    field_name: String,
     row_count: usize,
-  ) -> (String, Vec<Option<Value>>) {
+    options: &SerializeOptions,
+  ) -> Vec<Option<Value>> {
+    let values = Self::column_values(column, row_count, options);
+
+    if options.int64_mode == Int64Mode::Number {
+      return values;
+    }
+
+    values
+      .into_iter()
+      .map(|value| value.map(|value| options.apply_int64_mode(value)))
+      .collect()
+  }
+
+  /// Converts the first `row_count` values of `column`, nested types recurse into their children.
+  fn column_values(
+    column: &ArrayRef,
+    row_count: usize,
+    options: &SerializeOptions,
+  ) -> Vec<Option<Value>> {
     let mut column_values = Vec::with_capacity(row_count);
     let array_data = column.into_data();
 
@@ -250,7 +378,7 @@ impl LibrarySerializer {
           });
         }
       }
-      DataType::Utf8 | DataType::LargeUtf8 => {
+      DataType::Utf8 => {
         let arr = StringArray::from(array_data);
         for i in 0..row_count {
           column_values.push(if arr.is_null(i) {
@@ -260,52 +388,377 @@ impl LibrarySerializer {
           });
         }
       }
-      DataType::Duration(time_unit) => {
-        Self::serialize_duration_column(&array_data, time_unit, row_count, &mut column_values);
+      DataType::LargeUtf8 => {
+        let arr = LargeStringArray::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::String(arr.value(i).to_string()))
+          });
+        }
       }
-      DataType::Timestamp(time_unit, _) => {
-        Self::serialize_timestamp_column(&array_data, time_unit, row_count, &mut column_values);
+      DataType::Binary => {
+        let arr = BinaryArray::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::Binary(arr.value(i).to_vec()))
+          });
+        }
       }
-      DataType::Time32(time_unit) => {
-        Self::serialize_time32_column(&array_data, time_unit, row_count, &mut column_values);
+      DataType::LargeBinary => {
+        let arr = LargeBinaryArray::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::Binary(arr.value(i).to_vec()))
+          });
+        }
+      }
+      DataType::BinaryView => {
+        let arr = BinaryViewArray::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::Binary(arr.value(i).to_vec()))
+          });
+        }
+      }
+      DataType::FixedSizeBinary(_) => {
+        let arr = FixedSizeBinaryArray::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::Binary(arr.value(i).to_vec()))
+          });
+        }
+      }
+      // Decimals are returned as strings, as they don't fit a JS number without losing precision
+      DataType::Decimal128(_, _) => {
+        let arr = Decimal128Array::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::String(arr.value_as_string(i)))
+          });
+        }
       }
-      DataType::Time64(time_unit) => {
-        Self::serialize_time64_column(&array_data, time_unit, row_count, &mut column_values);
+      DataType::Decimal256(_, _) => {
+        let arr = Decimal256Array::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::String(arr.value_as_string(i)))
+          });
+        }
       }
-      DataType::FixedSizeList(field, size) => {
+      DataType::Duration(time_unit) => match options.temporal_mode {
+        Some(mode) => Self::serialize_raw_column(column, row_count, &mut column_values, |value| {
+          duration_value(value, time_unit, mode)
+        }),
+        None => {
+          Self::serialize_duration_column(&array_data, time_unit, row_count, &mut column_values);
+        }
+      },
+      DataType::Timestamp(time_unit, tz) => match options.timestamp_mode {
+        Some(mode) => {
+          let tz = parse_timezone(tz.as_deref());
+          Self::serialize_raw_column(column, row_count, &mut column_values, |value| {
+            timestamp_value(value, time_unit, tz.as_ref(), mode)
+          });
+        }
+        None => {
+          Self::serialize_timestamp_column(&array_data, time_unit, row_count, &mut column_values);
+        }
+      },
+      DataType::Time32(time_unit) => match options.temporal_mode {
+        Some(mode) => Self::serialize_raw_column(column, row_count, &mut column_values, |value| {
+          time_value(value, time_unit, mode)
+        }),
+        None => {
+          Self::serialize_time32_column(&array_data, time_unit, row_count, &mut column_values);
+        }
+      },
+      DataType::Time64(time_unit) => match options.temporal_mode {
+        Some(mode) => Self::serialize_raw_column(column, row_count, &mut column_values, |value| {
+          time_value(value, time_unit, mode)
+        }),
+        None => {
+          Self::serialize_time64_column(&array_data, time_unit, row_count, &mut column_values);
+        }
+      },
+      DataType::FixedSizeList(_, _) => {
         let arr = FixedSizeListArray::from(array_data);
-        let list_size = *size as usize;
-
         for i in 0..row_count {
-          if arr.is_null(i) {
-            column_values.push(None);
+          column_values.push(if arr.is_null(i) {
+            None
           } else {
-            let child_array = arr.value(i);
-            let list_values =
-              Self::extract_list_values(&child_array, field.data_type(), Some(list_size));
-            column_values.push(Some(Value::FixedList(list_values)));
-          }
+            Some(Value::FixedList(Self::extract_list_values(
+              &arr.value(i),
+              options,
+            )))
+          });
         }
       }
-      DataType::List(field) | DataType::LargeList(field) => {
+      DataType::List(_) => {
         let arr = ListArray::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::List(Self::extract_list_values(
+              &arr.value(i),
+              options,
+            )))
+          });
+        }
+      }
+      DataType::LargeList(_) => {
+        let arr = LargeListArray::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::List(Self::extract_list_values(
+              &arr.value(i),
+              options,
+            )))
+          });
+        }
+      }
+      DataType::ListView(_) => {
+        let arr = column.as_list_view::<i32>();
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::List(Self::extract_list_values(
+              &arr.value(i),
+              options,
+            )))
+          });
+        }
+      }
+      DataType::LargeListView(_) => {
+        let arr = column.as_list_view::<i64>();
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::List(Self::extract_list_values(
+              &arr.value(i),
+              options,
+            )))
+          });
+        }
+      }
+      DataType::Struct(fields) => {
+        let arr = StructArray::from(array_data);
+        let mut children: Vec<_> = fields
+          .iter()
+          .zip(arr.columns())
+          .map(|(field, child)| {
+            let values = Self::column_values(child, row_count, options);
+            (field.name().clone(), values.into_iter())
+          })
+          .collect();
 
         for i in 0..row_count {
-          if arr.is_null(i) {
-            column_values.push(None);
+          let entries: Vec<(String, Value)> = children
+            .iter_mut()
+            .map(|(name, values)| (name.clone(), values.next().flatten().unwrap_or(Value::Null)))
+            .collect();
+
+          column_values.push(if arr.is_null(i) {
+            None
+          } else {
+            Some(Value::Object(entries))
+          });
+        }
+      }
+      DataType::Map(_, _) => {
+        let arr = MapArray::from(array_data);
+        for i in 0..row_count {
+          column_values.push(if arr.is_null(i) {
+            None
           } else {
-            let child_array = arr.value(i);
-            let list_values = Self::extract_list_values(&child_array, field.data_type(), None);
-            column_values.push(Some(Value::List(list_values)));
-          }
+            Self::map_entries(&arr, i, options).map(Value::Object)
+          });
+        }
+      }
+      DataType::Union(_, _) => {
+        let arr = column.as_union();
+        for i in 0..row_count {
+          let value = arr.value(i);
+          column_values.push(Self::column_values(&value, 1, options).pop().flatten());
         }
       }
+      DataType::Interval(unit) => match options.temporal_mode {
+        Some(_) => Self::serialize_interval_column(column, unit, row_count, &mut column_values),
+        None => Self::serialize_formatted_column(column, row_count, &mut column_values),
+      },
+      DataType::Dictionary(_, _) => {
+        Self::serialize_dictionary_column(column, row_count, options, &mut column_values);
+      }
       _ => {
         column_values.resize(row_count, Some(Value::Fallback));
       }
     }
 
-    (field_name, column_values)
+    column_values
+  }
+
+  /// Converts temporal columns from their raw integer values, widened to i64.
+  fn serialize_raw_column(
+    column: &ArrayRef,
+    row_count: usize,
+    column_values: &mut Vec<Option<Value>>,
+    to_value: impl Fn(i64) -> Value,
+  ) {
+    // Time32 only casts to Int32, every other temporal type shares the i64 representation
+    let values = match column.data_type() {
+      DataType::Time32(_) => {
+        cast(column, &DataType::Int32).and_then(|v| cast(&v, &DataType::Int64))
+      }
+      _ => cast(column, &DataType::Int64),
+    };
+    let Ok(values) = values else {
+      column_values.resize(row_count, Some(Value::Fallback));
+      return;
+    };
+
+    column_values.extend(
+      values
+        .as_primitive::<Int64Type>()
+        .iter()
+        .take(row_count)
+        .map(|value| value.map(&to_value)),
+    );
+  }
+
+  fn serialize_interval_column(
+    column: &ArrayRef,
+    unit: &IntervalUnit,
+    row_count: usize,
+    column_values: &mut Vec<Option<Value>>,
+  ) {
+    match unit {
+      IntervalUnit::YearMonth => column_values.extend(
+        column
+          .as_primitive::<IntervalYearMonthType>()
+          .iter()
+          .take(row_count)
+          .map(|value| value.map(|months| interval_value(months, 0, 0))),
+      ),
+      IntervalUnit::DayTime => column_values.extend(
+        column
+          .as_primitive::<IntervalDayTimeType>()
+          .iter()
+          .take(row_count)
+          .map(|value| {
+            value.map(|value| interval_value(0, value.days, value.milliseconds as i64 * 1_000_000))
+          }),
+      ),
+      IntervalUnit::MonthDayNano => column_values.extend(
+        column
+          .as_primitive::<IntervalMonthDayNanoType>()
+          .iter()
+          .take(row_count)
+          .map(|value| {
+            value.map(|value| interval_value(value.months, value.days, value.nanoseconds))
+          }),
+      ),
+    }
+  }
+
+  /// Decodes the dictionary once per batch: every row shares the converted value of its key
+  /// instead of materializing its own copy. String dictionaries become [`SharedStrings`], so the
+  /// JS clients also create each string once per batch.
+  fn serialize_dictionary_column(
+    column: &ArrayRef,
+    row_count: usize,
+    options: &SerializeOptions,
+    column_values: &mut Vec<Option<Value>>,
+  ) {
+    let Some(dictionary) = column.as_any_dictionary_opt() else {
+      column_values.resize(row_count, Some(Value::Fallback));
+      return;
+    };
+
+    let values = dictionary.values();
+    if values.is_empty() {
+      column_values.resize(row_count, None);
+      return;
+    }
+
+    let values = Self::column_values(values, values.len(), options);
+    let strings = values
+      .iter()
+      .all(|value| matches!(value, None | Some(Value::String(_) | Value::Text(_))))
+      .then(|| {
+        Arc::new(SharedStrings::new(values.iter().map(|value| match value {
+          Some(Value::String(s) | Value::Text(s)) => s.as_str(),
+          _ => "",
+        })))
+      });
+    let keys = dictionary.normalized_keys();
+
+    for (i, key) in keys.into_iter().take(row_count).enumerate() {
+      column_values.push(match (&values[key], &strings) {
+        _ if column.is_null(i) => None,
+        (Some(_), Some(strings)) => Some(Value::Dictionary(DictionaryValue::new(
+          strings.clone(),
+          key,
+        ))),
+        (value, _) => value.clone(),
+      });
+    }
+  }
+
+  /// Map entries become the properties of an object, keys are converted to their display string.
+  fn map_entries(
+    arr: &MapArray,
+    i: usize,
+    options: &SerializeOptions,
+  ) -> Option<Vec<(String, Value)>> {
+    let entries = arr.value(i);
+    let keys =
+      ArrayFormatter::try_new(entries.column(0).as_ref(), &FormatOptions::default()).ok()?;
+    let values = Self::column_values(entries.column(1), entries.len(), options);
+
+    Some(
+      values
+        .into_iter()
+        .enumerate()
+        .map(|(j, value)| (keys.value(j).to_string(), value.unwrap_or(Value::Null)))
+        .collect(),
+    )
+  }
+
+  fn serialize_formatted_column(
+    column: &ArrayRef,
+    row_count: usize,
+    column_values: &mut Vec<Option<Value>>,
+  ) {
+    let Ok(formatter) = ArrayFormatter::try_new(column.as_ref(), &FormatOptions::default()) else {
+      column_values.resize(row_count, Some(Value::Fallback));
+      return;
+    };
+
+    for i in 0..row_count {
+      column_values.push(if column.is_null(i) {
+        None
+      } else {
+        Some(Value::String(formatter.value(i).to_string()))
+      });
+    }
   }
 
   fn serialize_duration_column(
@@ -480,64 +933,159 @@ impl LibrarySerializer {
     }
   }
 
-  fn extract_list_values(
-    array: &ArrayRef,
-    data_type: &DataType,
-    _size: Option<usize>,
-  ) -> Vec<Value> {
-    let size = match _size {
-      Some(size) => size,
-      None => array.len(),
+  fn extract_list_values(array: &ArrayRef, options: &SerializeOptions) -> Vec<Value> {
+    Self::column_values(array, array.len(), options)
+      .into_iter()
+      .map(|value| value.unwrap_or(Value::Null))
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::client::options::{TemporalMode, TimestampMode};
+  use arrow::array::IntervalMonthDayNanoArray;
+  use arrow::array::{DictionaryArray, Int64Builder, LargeListBuilder};
+  use arrow::datatypes::Field;
+  use arrow::datatypes::Int32Type;
+  use arrow::datatypes::IntervalMonthDayNano;
+
+  #[test]
+  fn test_nested_values() {
+    let mut lists = LargeListBuilder::new(Int64Builder::new());
+    lists.append_value([Some(1), None]);
+    lists.append_null();
+    let lists: ArrayRef = Arc::new(lists.finish());
+
+    let structs: ArrayRef = Arc::new(StructArray::from(vec![
+      (
+        Arc::new(Field::new("id", DataType::Binary, false)),
+        Arc::new(BinaryArray::from(vec![b"ab".as_slice(), b"c".as_slice()])) as ArrayRef,
+      ),
+      (
+        Arc::new(Field::new("price", DataType::Decimal128(5, 2), false)),
+        Arc::new(
+          Decimal128Array::from(vec![12345, -5])
+            .with_precision_and_scale(5, 2)
+            .unwrap(),
+        ) as ArrayRef,
+      ),
+    ]));
+
+    let lists = LibrarySerializer::column_values(&lists, 2, &SerializeOptions::default());
+    assert!(matches!(
+      lists[0].as_ref(),
+      Some(Value::List(values)) if matches!(values[..], [Value::Int64(1), Value::Null])
+    ));
+    assert!(lists[1].is_none());
+
+    let structs = LibrarySerializer::column_values(&structs, 2, &SerializeOptions::default());
+    let Some(Value::Object(entries)) = &structs[0] else {
+      panic!("expected an object, got {:?}", structs[0]);
     };
+    assert!(matches!(&entries[0], (name, Value::Binary(bytes)) if name == "id" && bytes == b"ab"));
+    assert!(matches!(&entries[1], (_, Value::String(price)) if price == "123.45"));
+  }
 
-    let mut values = Vec::with_capacity(size);
+  #[test]
+  fn test_timestamp_mode() {
+    let times: ArrayRef =
+      Arc::new(TimestampMillisecondArray::from(vec![Some(1_500), None]).with_timezone("+02:00"));
+    let options = SerializeOptions {
+      timestamp_mode: Some(TimestampMode::String),
+      ..Default::default()
+    };
 
-    for i in 0..size {
-      if array.is_null(i) {
-        values.push(Value::Null);
-        continue;
-      }
+    let values = LibrarySerializer::column_values(&times, 2, &options);
 
-      // Still lacks some dtypes, the whole serializer has to be refactored in order to re-use code
-      let value = match data_type {
-        DataType::UInt16 => {
-          let arr = array.as_any().downcast_ref::<UInt16Array>().unwrap();
-          Value::U16(arr.value(i))
-        }
-        DataType::Utf8View => {
-          let arr = array.as_any().downcast_ref::<StringViewArray>().unwrap();
-          Value::Text(arr.value(i).to_string())
-        }
-        DataType::Int32 => {
-          let arr = array.as_any().downcast_ref::<Int32Array>().unwrap();
-          Value::Int32(arr.value(i))
-        }
-        DataType::Int64 => {
-          let arr = array.as_any().downcast_ref::<Int64Array>().unwrap();
-          Value::Int64(arr.value(i))
-        }
-        DataType::Float32 => {
-          let arr = array.as_any().downcast_ref::<Float32Array>().unwrap();
-          Value::F32(arr.value(i))
-        }
-        DataType::Float64 => {
-          let arr = array.as_any().downcast_ref::<Float64Array>().unwrap();
-          Value::F64(arr.value(i))
-        }
-        DataType::Utf8 | DataType::LargeUtf8 => {
-          let arr = array.as_any().downcast_ref::<StringArray>().unwrap();
-          Value::String(arr.value(i).to_string())
-        }
-        DataType::Boolean => {
-          let arr = array.as_any().downcast_ref::<BooleanArray>().unwrap();
-          Value::Bool(arr.value(i))
-        }
-        _ => Value::Fallback,
-      };
+    assert!(matches!(&values[0], Some(Value::String(s)) if s == "1970-01-01T02:00:01.500+02:00"));
+    assert!(values[1].is_none());
+  }
 
-      values.push(value);
-    }
+  #[test]
+  fn test_temporal_mode_interval() {
+    let intervals: ArrayRef = Arc::new(IntervalMonthDayNanoArray::from(vec![
+      IntervalMonthDayNano::new(1, 2, 3),
+    ]));
+    let options = SerializeOptions {
+      temporal_mode: Some(TemporalMode::Structured),
+      ..Default::default()
+    };
 
-    values
+    let values = LibrarySerializer::column_values(&intervals, 1, &options);
+
+    let Some(Value::Object(entries)) = &values[0] else {
+      panic!("expected an object, got {:?}", values[0]);
+    };
+    assert!(matches!(
+      entries[..],
+      [
+        (_, Value::Int32(1)),
+        (_, Value::Int32(2)),
+        (_, Value::Int64(3))
+      ]
+    ));
+  }
+
+  #[test]
+  fn test_int64_mode_auto() {
+    let counters: ArrayRef = Arc::new(Int64Array::from(vec![42, i64::MAX]));
+    let options = SerializeOptions {
+      int64_mode: Int64Mode::Auto,
+      ..Default::default()
+    };
+
+    let values = LibrarySerializer::serialize_column(&counters, 2, &options);
+
+    assert!(matches!(values[0], Some(Value::F64(n)) if n == 42.0));
+    assert!(matches!(values[1], Some(Value::BigInt(n)) if n == i64::MAX as i128));
+  }
+
+  #[tokio::test]
+  async fn test_rows_share_keys() {
+    let schema = Arc::new(arrow::datatypes::Schema::new(vec![
+      Field::new("host", DataType::Utf8, false),
+      Field::new("usage", DataType::Float64, true),
+    ]));
+    let batch = RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(StringArray::from(vec!["a", "b"])),
+        Arc::new(Float64Array::from(vec![Some(0.5), None])),
+      ],
+    )
+    .unwrap();
+
+    let rows = LibrarySerializer::serialize(Ok(batch), &SerializeOptions::default())
+      .await
+      .unwrap();
+
+    assert!(Arc::ptr_eq(&rows[0].layout, &rows[1].layout));
+    assert!(matches!(rows[0].get("usage"), Some(Value::F64(v)) if *v == 0.5));
+    assert!(rows[1].get("usage").is_none());
+    assert_eq!(
+      rows[1].iter().map(|(key, _)| key).collect::<Vec<_>>(),
+      vec!["host", "usage"]
+    );
+  }
+
+  #[test]
+  fn test_dictionary_values_are_shared() {
+    let tags: DictionaryArray<Int32Type> = vec![Some("a"), None, Some("a"), Some("b")]
+      .into_iter()
+      .collect();
+    let tags: ArrayRef = Arc::new(tags);
+
+    let values = LibrarySerializer::column_values(&tags, 4, &SerializeOptions::default());
+
+    let (Some(Value::Dictionary(first)), Some(Value::Dictionary(third))) = (&values[0], &values[2])
+    else {
+      panic!("expected dictionary values, got {values:?}");
+    };
+    assert!(Arc::ptr_eq(first.values(), third.values()));
+    assert_eq!(first.as_str(), "a");
+    assert!(values[1].is_none());
+    assert!(matches!(&values[3], Some(Value::Dictionary(tag)) if tag.as_str() == "b"));
   }
 }
@@ -1,24 +1,61 @@
+use crate::client::options::{
+  FlightOptions, Int64Mode, NullMode, QueryPayload, TemporalMode, TimestampMode,
+};
+use crate::error::Result;
+use crate::Value;
 use arrow::array::RecordBatch;
-use arrow_flight::error::Result as FlightResult;
-use napi::bindgen_prelude::ToNapiValue;
+use arrow::datatypes::SchemaRef;
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+#[cfg(feature = "napi")]
+use napi::bindgen_prelude::{Buffer, ToNapiValue};
+#[cfg(feature = "napi")]
+use napi::sys::{napi_env, napi_value};
+#[cfg(feature = "napi")]
 use napi_derive::napi;
 use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::Deref;
 
+pub mod columnar_serializer;
 pub mod library_serializer;
 pub mod raw_serializer;
+#[cfg(feature = "napi")]
+pub mod registry;
+pub mod temporal;
+pub mod text_serializer;
 pub mod unsafe_serializer;
 
-#[napi(string_enum)]
+#[cfg_attr(feature = "napi", napi(string_enum))]
 #[derive(Debug, Clone)]
 pub enum Serializer {
-  #[napi(value = "unsafe")]
+  #[cfg_attr(feature = "napi", napi(value = "unsafe"))]
   Unsafe,
 
-  #[napi(value = "library")]
+  #[cfg_attr(feature = "napi", napi(value = "library"))]
   Library,
 
-  #[napi(value = "raw")]
+  #[cfg_attr(feature = "napi", napi(value = "raw"))]
   Raw,
+
+  #[cfg_attr(feature = "napi", napi(value = "columnar"))]
+  Columnar,
+
+  /// Arrow IPC stream: the schema once, then a chunk per record batch.
+  #[cfg_attr(feature = "napi", napi(value = "raw_stream"))]
+  RawStream,
+
+  /// Arrow IPC file, emitted as a single buffer once the query completed.
+  #[cfg_attr(feature = "napi", napi(value = "raw_file"))]
+  RawFile,
+
+  /// CSV text chunks, the header row first.
+  #[cfg_attr(feature = "napi", napi(value = "csv"))]
+  Csv,
+
+  /// Newline-delimited JSON text chunks.
+  #[cfg_attr(feature = "napi", napi(value = "ndjson"))]
+  Ndjson,
 }
 
 impl Default for Serializer {
@@ -27,10 +64,180 @@ impl Default for Serializer {
   }
 }
 
+/// Per query settings of the serializers, resolved from the client and query options.
+#[derive(Debug, Clone, Default)]
+pub struct SerializeOptions {
+  /// `None` keeps the `<value>_<unit>` strings.
+  pub timestamp_mode: Option<TimestampMode>,
+  pub int64_mode: Int64Mode,
+  /// `None` keeps the `<value>_<unit>` strings of durations and times and formats intervals.
+  pub temporal_mode: Option<TemporalMode>,
+  /// `None` keeps the default of each serializer: `null`, except `ndjson` which omits nulls.
+  pub null_mode: Option<NullMode>,
+}
+
+impl SerializeOptions {
+  pub(crate) fn new(options: Option<&FlightOptions>) -> Self {
+    Self {
+      timestamp_mode: options.and_then(|options| options.timestamp_mode),
+      int64_mode: options
+        .and_then(|options| options.int64_mode)
+        .unwrap_or_default(),
+      temporal_mode: options.and_then(|options| options.temporal_mode),
+      null_mode: options.and_then(|options| options.null_mode),
+    }
+  }
+
+  /// Applies the overrides of `query_payload` on top of the client settings.
+  pub(crate) fn for_query(&self, query_payload: &QueryPayload) -> Self {
+    Self {
+      timestamp_mode: query_payload.timestamp_mode.or(self.timestamp_mode),
+      int64_mode: query_payload.int64_mode.unwrap_or(self.int64_mode),
+      temporal_mode: query_payload.temporal_mode.or(self.temporal_mode),
+      null_mode: query_payload.null_mode.or(self.null_mode),
+    }
+  }
+
+  /// Converts 64-bit integers, nested ones included, to the representation of `int64_mode`.
+  pub(crate) fn apply_int64_mode(&self, value: Value) -> Value {
+    if self.int64_mode == Int64Mode::Number {
+      return value;
+    }
+
+    let integer = match value {
+      Value::Int64(i) | Value::Date64(i) => i as i128,
+      Value::U64(u) => u as i128,
+      Value::U128(u) => match i128::try_from(u) {
+        Ok(i) => i,
+        Err(_) if self.int64_mode == Int64Mode::String => return Value::String(u.to_string()),
+        Err(_) => return Value::U128(u),
+      },
+      Value::List(values) => {
+        return Value::List(
+          values
+            .into_iter()
+            .map(|v| self.apply_int64_mode(v))
+            .collect(),
+        )
+      }
+      Value::FixedList(values) => {
+        return Value::FixedList(
+          values
+            .into_iter()
+            .map(|v| self.apply_int64_mode(v))
+            .collect(),
+        )
+      }
+      Value::Object(entries) => {
+        return Value::Object(
+          entries
+            .into_iter()
+            .map(|(key, v)| (key, self.apply_int64_mode(v)))
+            .collect(),
+        )
+      }
+      value => return value,
+    };
+
+    match self.int64_mode {
+      Int64Mode::String => Value::String(integer.to_string()),
+      Int64Mode::Auto if integer.abs() <= MAX_SAFE_INTEGER => Value::F64(integer as f64),
+      _ => Value::BigInt(integer),
+    }
+  }
+}
+
+/// `Number.MAX_SAFE_INTEGER`, the largest integer a JS number represents exactly.
+const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+
 pub trait SerializerTrait {
-  type Output: ToNapiValue + Send + 'static;
+  /// Item of the query stream. The JS clients need it to implement `ToNapiValue`.
+  type Output: Send + 'static;
 
   fn serialize(
-    batch: FlightResult<RecordBatch>,
+    batch: Result<RecordBatch>,
+    options: &SerializeOptions,
   ) -> impl Future<Output = Option<Vec<Self::Output>>> + Send;
+
+  /// Emitted once before the output of the first batch, e.g. the header row of a text format.
+  fn header(_schema: &SchemaRef) -> Option<Self::Output> {
+    None
+  }
+}
+
+/// Object-safe form of [`SerializerTrait`], so the serializer of a query can be picked at
+/// runtime.
+pub(crate) trait BatchSerializer: Send + Sync {
+  type Output: Send + 'static;
+
+  fn serialize(
+    &self,
+    batch: Result<RecordBatch>,
+    options: SerializeOptions,
+  ) -> BoxFuture<'static, Option<Vec<Self::Output>>>;
+
+  fn header(&self, schema: &SchemaRef) -> Option<Self::Output>;
+}
+
+/// [`BatchSerializer`] of a serializer known at compile time.
+pub(crate) struct Typed<S>(PhantomData<fn() -> S>);
+
+impl<S> Default for Typed<S> {
+  fn default() -> Self {
+    Self(PhantomData)
+  }
+}
+
+impl<S: SerializerTrait + 'static> BatchSerializer for Typed<S> {
+  type Output = S::Output;
+
+  fn serialize(
+    &self,
+    batch: Result<RecordBatch>,
+    options: SerializeOptions,
+  ) -> BoxFuture<'static, Option<Vec<Self::Output>>> {
+    async move { S::serialize(batch, &options).await }.boxed()
+  }
+
+  fn header(&self, schema: &SchemaRef) -> Option<Self::Output> {
+    S::header(schema)
+  }
+}
+
+/// Bytes produced by the binary and text serializers, a `Buffer` in JS.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Chunk(Vec<u8>);
+
+impl Chunk {
+  pub fn into_inner(self) -> Vec<u8> {
+    self.0
+  }
+}
+
+impl Deref for Chunk {
+  type Target = [u8];
+
+  fn deref(&self) -> &[u8] {
+    &self.0
+  }
+}
+
+impl From<Vec<u8>> for Chunk {
+  fn from(bytes: Vec<u8>) -> Self {
+    Self(bytes)
+  }
+}
+
+#[cfg(feature = "napi")]
+impl From<Chunk> for Buffer {
+  fn from(chunk: Chunk) -> Self {
+    Buffer::from(chunk.0)
+  }
+}
+
+#[cfg(feature = "napi")]
+impl ToNapiValue for Chunk {
+  unsafe fn to_napi_value(env: napi_env, val: Self) -> napi::Result<napi_value> {
+    Buffer::to_napi_value(env, val.into())
+  }
 }
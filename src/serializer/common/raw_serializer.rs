@@ -1,14 +1,20 @@
-use crate::serializer::common::SerializerTrait;
+use crate::error::Result;
+use crate::serializer::common::{Chunk, SerializeOptions, SerializerTrait};
 use arrow::array::RecordBatch;
-use arrow::ipc::writer::StreamWriter;
-use arrow_flight::error::Result as FlightResult;
-use napi::bindgen_prelude::Buffer;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema};
+use arrow::error::ArrowError;
+use arrow::ipc::writer::{FileWriter, StreamWriter};
+use std::sync::Arc;
 
 pub struct RawSerializer;
 impl SerializerTrait for RawSerializer {
-  type Output = Buffer;
+  type Output = Chunk;
 
-  async fn serialize(batch: FlightResult<RecordBatch>) -> Option<Vec<Self::Output>> {
+  async fn serialize(
+    batch: Result<RecordBatch>,
+    _options: &SerializeOptions,
+  ) -> Option<Vec<Self::Output>> {
     if let Ok(batch) = batch {
       match serialize_record_batch_to_bytes(&batch) {
         Ok(bytes) => Some(vec![bytes.into()]),
@@ -31,3 +37,168 @@ fn serialize_record_batch_to_bytes(
   }
   Ok(buffer)
 }
+
+/// Writes all batches into a single IPC stream, readable as one Arrow table.
+pub(crate) fn serialize_table(batches: &[RecordBatch]) -> Result<Vec<u8>, ArrowError> {
+  let mut buffer = Vec::new();
+  if let Some(first) = batches.first() {
+    let mut stream_writer = StreamWriter::try_new(&mut buffer, &first.schema())?;
+    for batch in batches {
+      stream_writer.write(batch)?;
+    }
+    stream_writer.finish()?;
+  }
+  Ok(buffer)
+}
+
+/// Writes all batches into a single Arrow IPC file.
+///
+/// IPC files can't replace a dictionary between batches, while every batch of a query brings its
+/// own tag dictionaries, so dictionary columns are written with their value type.
+pub(crate) fn serialize_file(batches: &[RecordBatch]) -> Result<Vec<u8>, ArrowError> {
+  let mut buffer = Vec::new();
+  if let Some(first) = batches.first() {
+    let mut file_writer = FileWriter::try_new(&mut buffer, &decode_dictionaries(first)?.schema())?;
+    for batch in batches {
+      file_writer.write(&decode_dictionaries(batch)?)?;
+    }
+    file_writer.finish()?;
+  }
+  Ok(buffer)
+}
+
+/// Casts the dictionary columns of `batch` to their value type.
+fn decode_dictionaries(batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+  let schema = batch.schema();
+  if !schema
+    .fields()
+    .iter()
+    .any(|field| matches!(field.data_type(), DataType::Dictionary(_, _)))
+  {
+    return Ok(batch.clone());
+  }
+
+  let mut fields = Vec::with_capacity(schema.fields().len());
+  let mut columns = Vec::with_capacity(schema.fields().len());
+
+  for (field, column) in schema.fields().iter().zip(batch.columns()) {
+    match field.data_type() {
+      DataType::Dictionary(_, value_type) => {
+        columns.push(cast(column, value_type)?);
+        fields.push(
+          field
+            .as_ref()
+            .clone()
+            .with_data_type(value_type.as_ref().clone()),
+        );
+      }
+      _ => {
+        columns.push(column.clone());
+        fields.push(field.as_ref().clone());
+      }
+    }
+  }
+
+  let schema = Schema::new(fields).with_metadata(schema.metadata().clone());
+  RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+/// Encodes the batches of one query as one continuous Arrow IPC stream.
+///
+/// The first chunk starts with the schema message, the following ones only contain dictionary
+/// and record batch messages and `finish` returns the end-of-stream marker, so the chunks can be
+/// concatenated or fed one by one into an IPC stream reader.
+#[derive(Default)]
+pub(crate) struct IpcStreamEncoder {
+  writer: Option<StreamWriter<Vec<u8>>>,
+}
+
+impl IpcStreamEncoder {
+  pub(crate) fn write(&mut self, batch: &RecordBatch) -> Result<Vec<u8>, ArrowError> {
+    let writer = match &mut self.writer {
+      Some(writer) => writer,
+      None => self
+        .writer
+        .insert(StreamWriter::try_new(Vec::new(), &batch.schema())?),
+    };
+
+    writer.write(batch)?;
+    Ok(std::mem::take(writer.get_mut()))
+  }
+
+  pub(crate) fn finish(&mut self) -> Result<Vec<u8>, ArrowError> {
+    match &mut self.writer {
+      Some(writer) => {
+        writer.finish()?;
+        Ok(std::mem::take(writer.get_mut()))
+      }
+      None => Ok(Vec::new()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::array::{AsArray, DictionaryArray, Int64Array};
+  use arrow::datatypes::{Field, Int32Type};
+  use arrow::ipc::reader::{FileReader, StreamReader};
+  use std::io::Cursor;
+
+  fn batch(values: Vec<i64>) -> RecordBatch {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+      "value",
+      DataType::Int64,
+      false,
+    )]));
+    RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(values))]).unwrap()
+  }
+
+  #[test]
+  fn test_ipc_stream_chunks_concatenate() {
+    let mut encoder = IpcStreamEncoder::default();
+    let mut stream = encoder.write(&batch(vec![1, 2])).unwrap();
+    let second = encoder.write(&batch(vec![3])).unwrap();
+    let end = encoder.finish().unwrap();
+
+    assert!(second.len() < stream.len());
+    stream.extend(second);
+    stream.extend(end);
+
+    let rows: usize = StreamReader::try_new(Cursor::new(stream), None)
+      .unwrap()
+      .map(|batch| batch.unwrap().num_rows())
+      .sum();
+    assert_eq!(rows, 3);
+  }
+
+  #[test]
+  fn test_ipc_file() {
+    let file = serialize_file(&[batch(vec![1, 2]), batch(vec![3])]).unwrap();
+    let reader = FileReader::try_new(Cursor::new(file), None).unwrap();
+    assert_eq!(reader.num_batches(), 2);
+  }
+
+  #[test]
+  fn test_ipc_file_with_tag_dictionaries() {
+    let tags = |values: Vec<&str>| {
+      let host: DictionaryArray<Int32Type> = values.into_iter().collect();
+      let schema = Arc::new(Schema::new(vec![Field::new(
+        "host",
+        host.data_type().clone(),
+        false,
+      )]));
+      RecordBatch::try_new(schema, vec![Arc::new(host)]).unwrap()
+    };
+
+    let file = serialize_file(&[tags(vec!["a", "b"]), tags(vec!["c"])]).unwrap();
+    let batches: Vec<_> = FileReader::try_new(Cursor::new(file), None)
+      .unwrap()
+      .map(|batch| batch.unwrap())
+      .collect();
+
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[1].schema().field(0).data_type(), &DataType::Utf8);
+    assert_eq!(batches[1].column(0).as_string::<i32>().value(0), "c");
+  }
+}
@@ -0,0 +1,118 @@
+use crate::error::{Error, Result};
+use crate::serializer::common::{BatchSerializer, SerializeOptions, SerializerTrait};
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use napi::bindgen_prelude::ToNapiValue;
+use napi::sys::{napi_env, napi_value};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// Output of a registered serializer, converted to JS by the `ToNapiValue` impl of the
+/// serializer's own output type.
+pub struct RegisteredOutput(Box<dyn FnOnce(napi_env) -> napi::Result<napi_value> + Send>);
+
+impl RegisteredOutput {
+  fn new<T: ToNapiValue + Send + 'static>(value: T) -> Self {
+    Self(Box::new(move |env| unsafe { T::to_napi_value(env, value) }))
+  }
+}
+
+impl ToNapiValue for RegisteredOutput {
+  unsafe fn to_napi_value(env: napi_env, val: Self) -> napi::Result<napi_value> {
+    (val.0)(env)
+  }
+}
+
+struct Registered<S>(PhantomData<fn() -> S>);
+
+impl<S> BatchSerializer for Registered<S>
+where
+  S: SerializerTrait + 'static,
+  S::Output: ToNapiValue,
+{
+  type Output = RegisteredOutput;
+
+  fn serialize(
+    &self,
+    batch: Result<RecordBatch>,
+    options: SerializeOptions,
+  ) -> BoxFuture<'static, Option<Vec<Self::Output>>> {
+    async move {
+      let items = S::serialize(batch, &options).await?;
+      Some(items.into_iter().map(RegisteredOutput::new).collect())
+    }
+    .boxed()
+  }
+
+  fn header(&self, schema: &SchemaRef) -> Option<Self::Output> {
+    S::header(schema).map(RegisteredOutput::new)
+  }
+}
+
+pub(crate) type RegisteredSerializer = Arc<dyn BatchSerializer<Output = RegisteredOutput>>;
+
+fn registry() -> &'static RwLock<HashMap<String, RegisteredSerializer>> {
+  static REGISTRY: OnceLock<RwLock<HashMap<String, RegisteredSerializer>>> = OnceLock::new();
+  REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers `S` under `name`, so JS can run queries with it through `queryWith(name, payload)`.
+///
+/// Meant to be called by addons that link this crate, e.g. from their module initializer.
+/// Registering a name again replaces the previous serializer.
+pub fn register_serializer<S>(name: impl Into<String>)
+where
+  S: SerializerTrait + 'static,
+  S::Output: ToNapiValue,
+{
+  registry()
+    .write()
+    .unwrap_or_else(PoisonError::into_inner)
+    .insert(name.into(), Arc::new(Registered::<S>(PhantomData)));
+}
+
+/// Names of all registered serializers.
+pub fn registered_serializers() -> Vec<String> {
+  registry()
+    .read()
+    .unwrap_or_else(PoisonError::into_inner)
+    .keys()
+    .cloned()
+    .collect()
+}
+
+pub(crate) fn registered_serializer(name: &str) -> Result<RegisteredSerializer> {
+  registry()
+    .read()
+    .unwrap_or_else(PoisonError::into_inner)
+    .get(name)
+    .cloned()
+    .ok_or_else(|| Error::Request(format!("No serializer registered as '{name}'")))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::serializer::common::text_serializer::CsvSerializer;
+  use arrow::datatypes::{DataType, Field, Schema};
+
+  #[tokio::test]
+  async fn test_registered_serializer() {
+    register_serializer::<CsvSerializer>("test_csv");
+
+    let schema = Arc::new(Schema::new(vec![Field::new("host", DataType::Utf8, false)]));
+    let batch = RecordBatch::new_empty(schema.clone());
+    let serializer = registered_serializer("test_csv").unwrap();
+
+    assert!(registered_serializers().contains(&"test_csv".to_string()));
+    assert!(serializer.header(&schema).is_some());
+    assert!(serializer
+      .serialize(Ok(batch), SerializeOptions::default())
+      .await
+      .is_some());
+    assert!(registered_serializer("missing").is_err());
+  }
+}
@@ -0,0 +1,158 @@
+use crate::client::options::{TemporalMode, TimestampMode};
+use crate::Value;
+use arrow::array::timezone::Tz;
+use arrow::datatypes::TimeUnit;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Converts a raw timestamp of `unit` since the Unix epoch into the value requested by `mode`.
+///
+/// The stored value is always relative to UTC, the timezone of the column only changes the
+/// offset of RFC3339 strings.
+pub(crate) fn timestamp_value(
+  value: i64,
+  unit: &TimeUnit,
+  tz: Option<&Tz>,
+  mode: TimestampMode,
+) -> Value {
+  match mode {
+    TimestampMode::Date => Value::Date(epoch_millis(value, unit) as f64),
+    TimestampMode::Number => Value::Int64(epoch_millis(value, unit)),
+    TimestampMode::BigInt => Value::BigInt(to_nanos(value, unit)),
+    TimestampMode::String => {
+      let nanos = to_nanos(value, unit);
+      let seconds = nanos.div_euclid(NANOS_PER_SECOND) as i64;
+      let subsec = nanos.rem_euclid(NANOS_PER_SECOND) as u32;
+
+      match DateTime::<Utc>::from_timestamp(seconds, subsec) {
+        Some(time) => Value::String(match tz {
+          Some(tz) => time
+            .with_timezone(tz)
+            .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+          None => time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        }),
+        None => Value::Fallback,
+      }
+    }
+  }
+}
+
+/// Converts a duration of `unit` into the value requested by `mode`.
+pub(crate) fn duration_value(value: i64, unit: &TimeUnit, mode: TemporalMode) -> Value {
+  match mode {
+    TemporalMode::Structured => Value::Object(vec![
+      ("value".to_string(), Value::Int64(value)),
+      (
+        "unit".to_string(),
+        Value::String(unit_name(unit).to_string()),
+      ),
+    ]),
+    TemporalMode::Nanoseconds => Value::BigInt(to_nanos(value, unit)),
+  }
+}
+
+/// Converts a time of day, `value` units since midnight, into the value requested by `mode`.
+pub(crate) fn time_value(value: i64, unit: &TimeUnit, mode: TemporalMode) -> Value {
+  let nanos = to_nanos(value, unit);
+
+  match mode {
+    TemporalMode::Structured => {
+      let seconds = nanos.div_euclid(NANOS_PER_SECOND);
+      Value::String(format!(
+        "{:02}:{:02}:{:02}.{:09}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        nanos.rem_euclid(NANOS_PER_SECOND)
+      ))
+    }
+    TemporalMode::Nanoseconds => Value::BigInt(nanos),
+  }
+}
+
+/// Intervals mix calendar and fixed units, so all three components are kept.
+pub(crate) fn interval_value(months: i32, days: i32, nanoseconds: i64) -> Value {
+  Value::Object(vec![
+    ("months".to_string(), Value::Int32(months)),
+    ("days".to_string(), Value::Int32(days)),
+    ("nanoseconds".to_string(), Value::Int64(nanoseconds)),
+  ])
+}
+
+fn unit_name(unit: &TimeUnit) -> &'static str {
+  match unit {
+    TimeUnit::Second => "s",
+    TimeUnit::Millisecond => "ms",
+    TimeUnit::Microsecond => "us",
+    TimeUnit::Nanosecond => "ns",
+  }
+}
+
+/// Parses the timezone of a `Timestamp` column, named zones and fixed offsets are supported.
+pub(crate) fn parse_timezone(tz: Option<&str>) -> Option<Tz> {
+  tz.and_then(|tz| tz.parse::<Tz>().ok())
+}
+
+fn to_nanos(value: i64, unit: &TimeUnit) -> i128 {
+  let value = value as i128;
+  match unit {
+    TimeUnit::Second => value * NANOS_PER_SECOND,
+    TimeUnit::Millisecond => value * 1_000_000,
+    TimeUnit::Microsecond => value * 1_000,
+    TimeUnit::Nanosecond => value,
+  }
+}
+
+fn epoch_millis(value: i64, unit: &TimeUnit) -> i64 {
+  match unit {
+    TimeUnit::Second => value.saturating_mul(1_000),
+    TimeUnit::Millisecond => value,
+    TimeUnit::Microsecond => value.div_euclid(1_000),
+    TimeUnit::Nanosecond => value.div_euclid(1_000_000),
+  }
+}
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_timestamp_string_honors_timezone() {
+    let value = 1_700_000_000_123_000_000;
+    let tz = parse_timezone(Some("Europe/Berlin"));
+
+    let utc = timestamp_value(value, &TimeUnit::Nanosecond, None, TimestampMode::String);
+    let berlin = timestamp_value(
+      value,
+      &TimeUnit::Nanosecond,
+      tz.as_ref(),
+      TimestampMode::String,
+    );
+
+    assert!(matches!(utc, Value::String(s) if s == "2023-11-14T22:13:20.123Z"));
+    assert!(matches!(berlin, Value::String(s) if s == "2023-11-14T23:13:20.123+01:00"));
+  }
+
+  #[test]
+  fn test_timestamp_numbers() {
+    let before_epoch = timestamp_value(-1, &TimeUnit::Microsecond, None, TimestampMode::Number);
+    let nanos = timestamp_value(5, &TimeUnit::Second, None, TimestampMode::BigInt);
+
+    assert!(matches!(before_epoch, Value::Int64(-1)));
+    assert!(matches!(nanos, Value::BigInt(5_000_000_000)));
+  }
+
+  #[test]
+  fn test_time_and_duration() {
+    let time = time_value(
+      45_296_000_001,
+      &TimeUnit::Microsecond,
+      TemporalMode::Structured,
+    );
+    let duration = duration_value(-3, &TimeUnit::Millisecond, TemporalMode::Nanoseconds);
+
+    assert!(matches!(time, Value::String(s) if s == "12:34:56.000001000"));
+    assert!(matches!(duration, Value::BigInt(-3_000_000)));
+  }
+}
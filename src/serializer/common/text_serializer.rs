@@ -0,0 +1,118 @@
+use crate::client::options::NullMode;
+use crate::error::Result;
+use crate::serializer::common::{Chunk, SerializeOptions, SerializerTrait};
+use arrow::array::RecordBatch;
+use arrow::csv::WriterBuilder;
+use arrow::datatypes::SchemaRef;
+use arrow::error::ArrowError;
+use arrow::json::writer::{LineDelimited, WriterBuilder as JsonWriterBuilder};
+
+/// Encodes every batch as CSV rows. The header row is emitted once, before the first batch,
+/// and timestamps are written as RFC3339.
+pub struct CsvSerializer;
+impl SerializerTrait for CsvSerializer {
+  type Output = Chunk;
+
+  async fn serialize(
+    batch: Result<RecordBatch>,
+    _options: &SerializeOptions,
+  ) -> Option<Vec<Self::Output>> {
+    let batch = batch.ok()?;
+    encode_csv(&batch, false)
+      .ok()
+      .map(|bytes| vec![bytes.into()])
+  }
+
+  fn header(schema: &SchemaRef) -> Option<Self::Output> {
+    encode_csv(&RecordBatch::new_empty(schema.clone()), true)
+      .ok()
+      .map(Chunk::from)
+  }
+}
+
+/// Encodes every batch as newline-delimited JSON, one object per row.
+pub struct NdjsonSerializer;
+impl SerializerTrait for NdjsonSerializer {
+  type Output = Chunk;
+
+  async fn serialize(
+    batch: Result<RecordBatch>,
+    options: &SerializeOptions,
+  ) -> Option<Vec<Self::Output>> {
+    let batch = batch.ok()?;
+    let explicit_nulls = options.null_mode == Some(NullMode::Null);
+    encode_ndjson(&batch, explicit_nulls)
+      .ok()
+      .map(|bytes| vec![bytes.into()])
+  }
+}
+
+fn encode_csv(batch: &RecordBatch, header: bool) -> Result<Vec<u8>, ArrowError> {
+  let mut writer = WriterBuilder::new().with_header(header).build(Vec::new());
+  writer.write(batch)?;
+  Ok(writer.into_inner())
+}
+
+fn encode_ndjson(batch: &RecordBatch, explicit_nulls: bool) -> Result<Vec<u8>, ArrowError> {
+  let mut writer = JsonWriterBuilder::new()
+    .with_explicit_nulls(explicit_nulls)
+    .build::<_, LineDelimited>(Vec::new());
+  writer.write(batch)?;
+  writer.finish()?;
+  Ok(writer.into_inner())
+}
+
+/// Joins text chunks into the single buffer returned by `query_all`.
+pub(crate) fn concat_chunks(chunks: Vec<Chunk>) -> Chunk {
+  let mut bytes = Vec::with_capacity(chunks.iter().map(|chunk| chunk.len()).sum());
+  for chunk in &chunks {
+    bytes.extend_from_slice(chunk);
+  }
+  bytes.into()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::array::{Float64Array, StringArray};
+  use arrow::datatypes::{DataType, Field, Schema};
+  use std::sync::Arc;
+
+  fn batch() -> RecordBatch {
+    let schema = Arc::new(Schema::new(vec![
+      Field::new("host", DataType::Utf8, false),
+      Field::new("usage", DataType::Float64, true),
+    ]));
+    RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(StringArray::from(vec!["a", "b"])),
+        Arc::new(Float64Array::from(vec![Some(0.5), None])),
+      ],
+    )
+    .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_csv_header_once() {
+    let header = CsvSerializer::header(&batch().schema()).unwrap();
+    let rows = CsvSerializer::serialize(Ok(batch()), &SerializeOptions::default())
+      .await
+      .unwrap();
+
+    assert_eq!(&header[..], b"host,usage\n");
+    assert_eq!(&rows[0][..], b"a,0.5\nb,\n");
+  }
+
+  #[tokio::test]
+  async fn test_ndjson() {
+    let rows = NdjsonSerializer::serialize(Ok(batch()), &SerializeOptions::default())
+      .await
+      .unwrap();
+
+    assert_eq!(
+      std::str::from_utf8(&rows[0]).unwrap(),
+      "{\"host\":\"a\",\"usage\":0.5}\n{\"host\":\"b\"}\n"
+    );
+  }
+}
@@ -1,16 +1,123 @@
-use crate::serializer::common::SerializerTrait;
+use crate::client::options::NullMode;
+use crate::error::Result;
+#[cfg(feature = "napi")]
+use crate::serializer::common::columnar_serializer::{create_object, set_property};
+use crate::serializer::common::{SerializeOptions, SerializerTrait};
 use arrow::array::RecordBatch;
-use arrow_flight::error::Result as FlightResult;
+#[cfg(feature = "napi")]
+use napi::bindgen_prelude::ToNapiValue;
+#[cfg(feature = "napi")]
+use napi::sys::{napi_env, napi_value};
+use std::ops::Deref;
 
 pub struct UnsafeSerializer;
 impl SerializerTrait for UnsafeSerializer {
-  type Output = serde_json::Map<String, serde_json::Value>;
+  type Output = JsonRow;
 
-  async fn serialize(batch: FlightResult<RecordBatch>) -> Option<Vec<Self::Output>> {
+  async fn serialize(
+    batch: Result<RecordBatch>,
+    options: &SerializeOptions,
+  ) -> Option<Vec<Self::Output>> {
     if let Ok(batch) = batch {
-      serde_arrow::from_record_batch::<Vec<serde_json::Map<String, serde_json::Value>>>(&batch).ok()
+      let rows =
+        serde_arrow::from_record_batch::<Vec<serde_json::Map<String, serde_json::Value>>>(&batch)
+          .ok()?;
+      let null_mode = options.null_mode.unwrap_or(NullMode::Null);
+
+      Some(
+        rows
+          .into_iter()
+          .map(|mut values| {
+            if null_mode == NullMode::Omit {
+              values.retain(|_, value| !value.is_null());
+            }
+            JsonRow { values, null_mode }
+          })
+          .collect(),
+      )
     } else {
       None
     }
   }
 }
+
+/// Row of the `unsafe` serializer, derefs to its JSON object.
+#[derive(Debug, Clone)]
+pub struct JsonRow {
+  values: serde_json::Map<String, serde_json::Value>,
+  null_mode: NullMode,
+}
+
+impl JsonRow {
+  pub fn into_inner(self) -> serde_json::Map<String, serde_json::Value> {
+    self.values
+  }
+}
+
+impl Deref for JsonRow {
+  type Target = serde_json::Map<String, serde_json::Value>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.values
+  }
+}
+
+#[cfg(feature = "napi")]
+impl ToNapiValue for JsonRow {
+  unsafe fn to_napi_value(env: napi_env, val: Self) -> napi::Result<napi_value> {
+    if val.null_mode != NullMode::Undefined {
+      return ToNapiValue::to_napi_value(env, val.values);
+    }
+
+    // JSON has no `undefined`, so only the top level nulls are converted here
+    let row = create_object(env)?;
+    for (key, value) in val.values {
+      let value = match value {
+        serde_json::Value::Null => ToNapiValue::to_napi_value(env, ())?,
+        value => ToNapiValue::to_napi_value(env, value)?,
+      };
+      set_property(env, row, key, value)?;
+    }
+
+    Ok(row)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use arrow::array::{Float64Array, StringArray};
+  use arrow::datatypes::{DataType, Field, Schema};
+  use std::sync::Arc;
+
+  #[tokio::test]
+  async fn test_omit_nulls() {
+    let schema = Arc::new(Schema::new(vec![
+      Field::new("host", DataType::Utf8, false),
+      Field::new("usage", DataType::Float64, true),
+    ]));
+    let batch = RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(StringArray::from(vec!["a", "b"])),
+        Arc::new(Float64Array::from(vec![Some(0.5), None])),
+      ],
+    )
+    .unwrap();
+    let options = SerializeOptions {
+      null_mode: Some(NullMode::Omit),
+      ..Default::default()
+    };
+
+    let rows = UnsafeSerializer::serialize(Ok(batch.clone()), &options)
+      .await
+      .unwrap();
+    let defaults = UnsafeSerializer::serialize(Ok(batch), &SerializeOptions::default())
+      .await
+      .unwrap();
+
+    assert!(rows[0].contains_key("usage"));
+    assert!(!rows[1].contains_key("usage"));
+    assert!(defaults[1]["usage"].is_null());
+  }
+}
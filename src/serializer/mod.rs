@@ -3,3 +3,4 @@ pub mod browser;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod common;
+pub mod shared_strings;
@@ -0,0 +1,269 @@
+#[cfg(feature = "napi")]
+use napi::bindgen_prelude::ToNapiValue;
+#[cfg(feature = "napi")]
+use napi::check_status;
+#[cfg(feature = "napi")]
+use napi::sys::{napi_env, napi_ref, napi_value};
+#[cfg(feature = "napi")]
+use std::ffi::c_void;
+use std::fmt;
+#[cfg(feature = "napi")]
+use std::sync::{Mutex, OnceLock, PoisonError};
+#[cfg(feature = "napi")]
+use std::thread::ThreadId;
+
+/// Strings shared by every row of a batch, e.g. its column names or the values of a dictionary
+/// column.
+///
+/// The JS clients create each string once, when the first row of the batch is converted, and
+/// keep it alive with a reference, so the following rows reuse the same JS value. The references
+/// are deleted on the JS thread whichever thread drops the strings last.
+pub struct SharedStrings {
+  values: Box<[Box<str>]>,
+  #[cfg(feature = "napi")]
+  js: OnceLock<JsStrings>,
+}
+
+impl SharedStrings {
+  pub fn new<S: Into<Box<str>>>(values: impl IntoIterator<Item = S>) -> Self {
+    Self {
+      values: values.into_iter().map(Into::into).collect(),
+      #[cfg(feature = "napi")]
+      js: OnceLock::new(),
+    }
+  }
+
+  pub fn get(&self, index: usize) -> Option<&str> {
+    self.values.get(index).map(AsRef::as_ref)
+  }
+
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &str> {
+    self.values.iter().map(AsRef::as_ref)
+  }
+
+  /// JS string of the value at `index`, created for the whole batch on first use.
+  ///
+  /// # Safety
+  ///
+  /// Must be called on the JS thread of `env`, like any `ToNapiValue` conversion.
+  #[cfg(feature = "napi")]
+  pub(crate) unsafe fn to_napi_value(
+    &self,
+    env: napi_env,
+    index: usize,
+  ) -> napi::Result<napi_value> {
+    let js = match self.js.get() {
+      Some(js) => js,
+      None => {
+        let _ = self.js.set(JsStrings::new(env, &self.values)?);
+        self.js.get().expect("initialized above")
+      }
+    };
+
+    // Rows of one batch are converted by the same environment, any other one gets a copy
+    if js.env != env {
+      return ToNapiValue::to_napi_value(env, self.values[index].as_ref());
+    }
+
+    let mut value = std::ptr::null_mut();
+    check_status!(napi::sys::napi_get_reference_value(
+      env,
+      js.refs[index],
+      &mut value
+    ))?;
+    Ok(value)
+  }
+}
+
+impl fmt::Debug for SharedStrings {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.values.iter()).finish()
+  }
+}
+
+/// References to the JS strings of a [`SharedStrings`].
+#[cfg(feature = "napi")]
+struct JsStrings {
+  env: napi_env,
+  thread: ThreadId,
+  refs: Box<[napi_ref]>,
+}
+
+// The references are only created, read and deleted on the JS thread of `env`, other threads
+// merely move them along with the rows or hand them to `ORPHANED_REFS`.
+#[cfg(feature = "napi")]
+unsafe impl Send for JsStrings {}
+#[cfg(feature = "napi")]
+unsafe impl Sync for JsStrings {}
+
+/// Reference dropped off the JS thread, only deleted on the JS thread of its environment.
+#[cfg(feature = "napi")]
+struct OrphanedRef(napi_ref);
+
+#[cfg(feature = "napi")]
+unsafe impl Send for OrphanedRef {}
+
+#[cfg(feature = "napi")]
+static ORPHANED_REFS: OrphanedRefs<OrphanedRef> = OrphanedRefs::new();
+
+/// References dropped off the JS thread of their environment, e.g. with the rows of a cancelled
+/// stream, kept until the environment converts its next batch and deletes them.
+#[cfg(feature = "napi")]
+struct OrphanedRefs<T> {
+  envs: Mutex<Vec<(usize, Vec<T>)>>,
+}
+
+#[cfg(feature = "napi")]
+impl<T> OrphanedRefs<T> {
+  const fn new() -> Self {
+    Self {
+      envs: Mutex::new(Vec::new()),
+    }
+  }
+
+  /// Starts keeping the references of `env`, `false` when they are kept already.
+  fn watch(&self, env: usize) -> bool {
+    let mut envs = self.envs.lock().unwrap_or_else(PoisonError::into_inner);
+    if envs.iter().any(|(watched, _)| *watched == env) {
+      return false;
+    }
+    envs.push((env, Vec::new()));
+    true
+  }
+
+  /// Keeps `refs` until [`OrphanedRefs::take`], they are dropped when `env` is gone.
+  fn push(&self, env: usize, refs: impl IntoIterator<Item = T>) {
+    let mut envs = self.envs.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((_, orphans)) = envs.iter_mut().find(|(watched, _)| *watched == env) {
+      orphans.extend(refs);
+    }
+  }
+
+  fn take(&self, env: usize) -> Vec<T> {
+    let mut envs = self.envs.lock().unwrap_or_else(PoisonError::into_inner);
+    envs
+      .iter_mut()
+      .find(|(watched, _)| *watched == env)
+      .map(|(_, orphans)| std::mem::take(orphans))
+      .unwrap_or_default()
+  }
+
+  /// Stops keeping the references of `env`, which its teardown releases.
+  fn forget(&self, env: usize) {
+    let mut envs = self.envs.lock().unwrap_or_else(PoisonError::into_inner);
+    envs.retain(|(watched, _)| *watched != env);
+  }
+}
+
+#[cfg(feature = "napi")]
+unsafe extern "C" fn forget_orphaned_refs(env: *mut c_void) {
+  ORPHANED_REFS.forget(env as usize);
+}
+
+#[cfg(feature = "napi")]
+impl JsStrings {
+  unsafe fn new(env: napi_env, values: &[Box<str>]) -> napi::Result<Self> {
+    if ORPHANED_REFS.watch(env as usize) {
+      let status = check_status!(napi::sys::napi_add_env_cleanup_hook(
+        env,
+        Some(forget_orphaned_refs),
+        env as *mut c_void
+      ));
+      if let Err(e) = status {
+        ORPHANED_REFS.forget(env as usize);
+        return Err(e);
+      }
+    }
+    for orphan in ORPHANED_REFS.take(env as usize) {
+      napi::sys::napi_delete_reference(env, orphan.0);
+    }
+
+    let mut js = Self {
+      env,
+      thread: std::thread::current().id(),
+      refs: Box::default(),
+    };
+
+    let mut refs = Vec::with_capacity(values.len());
+    for value in values {
+      let value = ToNapiValue::to_napi_value(env, value.as_ref())?;
+      let mut reference = std::ptr::null_mut();
+      let status = check_status!(napi::sys::napi_create_reference(
+        env,
+        value,
+        1,
+        &mut reference
+      ));
+      if let Err(e) = status {
+        // Dropping `js` releases the references created so far
+        js.refs = refs.into_boxed_slice();
+        return Err(e);
+      }
+      refs.push(reference);
+    }
+
+    js.refs = refs.into_boxed_slice();
+    Ok(js)
+  }
+}
+
+#[cfg(feature = "napi")]
+impl Drop for JsStrings {
+  fn drop(&mut self) {
+    // The last row of a batch is usually dropped right after its conversion on the JS thread,
+    // references of rows dropped elsewhere are deleted with the next batch of the environment.
+    if std::thread::current().id() != self.thread {
+      let refs = std::mem::take(&mut self.refs);
+      ORPHANED_REFS.push(
+        self.env as usize,
+        refs.into_vec().into_iter().map(OrphanedRef),
+      );
+      return;
+    }
+
+    for reference in self.refs.iter() {
+      unsafe {
+        napi::sys::napi_delete_reference(self.env, *reference);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #[cfg(feature = "napi")]
+  #[test]
+  fn test_refs_dropped_on_other_thread() {
+    use super::OrphanedRefs;
+    use std::sync::Arc;
+
+    let orphans = Arc::new(OrphanedRefs::new());
+    assert!(orphans.watch(1));
+    assert!(!orphans.watch(1));
+
+    let dropped = Arc::clone(&orphans);
+    std::thread::spawn(move || {
+      dropped.push(1, [10, 11]);
+      // Environment already torn down
+      dropped.push(2, [20]);
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(orphans.take(1), vec![10, 11]);
+    assert!(orphans.take(1).is_empty());
+    assert!(orphans.take(2).is_empty());
+
+    orphans.forget(1);
+    orphans.push(1, [12]);
+    assert!(orphans.take(1).is_empty());
+  }
+}
@@ -1,4 +1,5 @@
 use crate::client::options::{Precision, TimeUnitV2, TimeUnitV3, WriteOptions};
+use crate::error::{Error, Result};
 use reqwest::Url;
 
 pub fn get_write_path(
@@ -6,7 +7,7 @@ pub fn get_write_path(
   database: String,
   org: Option<String>,
   _write_options: Option<WriteOptions>,
-) -> napi::Result<(Url, WriteOptions)> {
+) -> Result<(Url, WriteOptions)> {
   let write_options = _write_options.unwrap_or_default();
   let mut query_params: Vec<(String, String)> = Vec::new();
 
@@ -63,15 +64,10 @@ pub fn get_write_path(
     query_params.push((String::from("org"), org))
   }
 
-  let url = Url::parse_with_params(final_url.as_str(), &query_params);
+  let url = Url::parse_with_params(final_url.as_str(), &query_params)
+    .map_err(|e| Error::Request(format!("Error parsing URL: {e}")))?;
 
-  match url {
-    Ok(url) => Ok((url, write_options)),
-    Err(e) => {
-      println!("Error occurred in get_write_path: {e:?}");
-      Err(napi::Error::from_reason("Error parsing URL"))
-    }
-  }
+  Ok((url, write_options))
 }
 
 static WRITE_V3_PATH: &str = "/api/v3/write_lp";
@@ -54,8 +54,7 @@ async fn test_read_data_cloud_serverless() {
     .query(QueryPayload {
       database: "test".to_string(),
       query: r#"SELECT * FROM "tag_keys" "#.to_string(),
-      _type: None,
-      params: None,
+      ..Default::default()
     })
     .await;
 